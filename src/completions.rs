@@ -0,0 +1,130 @@
+//! Generates shell completion scripts and the man page from the command-line definition.
+//!
+//! Bash, zsh, fish and elvish use the completion engine of `clap_complete`: the script only
+//! registers the binary, which the shell runs with `COMPLETE=<shell>` on every completion, so
+//! project names are always current. Nushell has no such engine and gets a static script,
+//! whose project completer runs the hidden `complete-projects` subcommand instead.
+
+use crate::config::{CompletionShell, Config, cli_command};
+use crate::directory_scanner::{DirectoryEntry, DirectoryScanner};
+use clap_complete::CompletionCandidate;
+use clap_complete::env::{Bash, Elvish, EnvCompleter, Fish, Zsh};
+use clap_complete_nushell::Nushell;
+use std::collections::BTreeSet;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the binary, as used in the completion scripts.
+const BIN_NAME: &str = "tmux-sessionizer";
+
+/// Environment variable that makes the binary complete the command line instead of running.
+pub const COMPLETE_VAR: &str = "COMPLETE";
+
+/// Writes the completion script for `shell`.
+pub fn write_completions(shell: CompletionShell, buf: &mut dyn Write) -> io::Result<()> {
+    let completer: &dyn EnvCompleter = match shell {
+        CompletionShell::Bash => &Bash,
+        CompletionShell::Zsh => &Zsh,
+        CompletionShell::Fish => &Fish,
+        CompletionShell::Elvish => &Elvish,
+        CompletionShell::Nushell => return buf.write_all(nushell_completions().as_bytes()),
+    };
+    completer.write_registration(COMPLETE_VAR, BIN_NAME, BIN_NAME, BIN_NAME, buf)
+}
+
+/// Generates the static Nushell completions, with a custom completer for the direct selection.
+fn nushell_completions() -> String {
+    let mut script = Vec::new();
+    clap_complete::generate(Nushell, &mut cli_command(), BIN_NAME, &mut script);
+    let script = String::from_utf8_lossy(&script);
+
+    let completer = format!("nu-complete {BIN_NAME} direct_selection");
+    let definition = format!(
+        "  def \"{completer}\" [] {{\n    ^{BIN_NAME} complete-projects | lines | split column \"\\t\" value description\n  }}\n\n"
+    );
+    script
+        .replacen(
+            "module completions {\n\n",
+            &format!("module completions {{\n\n{definition}"),
+            1,
+        )
+        .replacen(
+            "    direct_selection?: string",
+            &format!("    direct_selection?: string@\"{completer}\""),
+            1,
+        )
+}
+
+/// Writes the man page in roff format.
+pub fn write_man_page(buf: &mut dyn Write) -> io::Result<()> {
+    clap_mangen::Man::new(cli_command()).render(buf)
+}
+
+/// Writes the projects offered for completion, one `<value>\t<path>` line each.
+pub fn write_project_list(buf: &mut dyn Write) -> io::Result<()> {
+    for (value, path) in project_completions() {
+        writeln!(buf, "{value}\t{}", path.display())?;
+    }
+    Ok(())
+}
+
+/// Returns the projects offered for the direct selection argument, for the completion engine.
+pub fn project_candidates() -> Vec<CompletionCandidate> {
+    project_completions()
+        .into_iter()
+        .map(|(value, path)| {
+            CompletionCandidate::new(value).help(Some(path.display().to_string().into()))
+        })
+        .collect()
+}
+
+/// Scans the search paths of the current configuration and returns every project as the value
+/// that selects it, with its path.
+///
+/// This runs on every completion, so problems are not reported: without a usable configuration,
+/// nothing is completed.
+fn project_completions() -> Vec<(String, PathBuf)> {
+    let config = match Config::for_completion() {
+        Ok(config) => config,
+        Err(e) => {
+            debug!(error = %e, "Cannot load the configuration for completion");
+            return Vec::new();
+        }
+    };
+    let entries = DirectoryScanner::new(&config).scan();
+    completion_values(&entries)
+}
+
+/// Returns, for every entry, the shortest trailing part of its path that selects only this
+/// entry when given as direct selection, sorted by value.
+///
+/// Direct selection matches path suffixes before display names, so the suffix is what
+/// completes to the intended project; an entry without a unique suffix gets its full path.
+pub(crate) fn completion_values(entries: &[DirectoryEntry]) -> Vec<(String, PathBuf)> {
+    let paths: BTreeSet<&Path> = entries
+        .iter()
+        .map(|entry| entry.resolved_path.as_path())
+        .collect();
+
+    let mut values: Vec<(String, PathBuf)> = paths
+        .iter()
+        .map(|path| {
+            let components: Vec<_> = path.components().collect();
+            let unique_suffix = (1..components.len())
+                .map(|length| {
+                    components[components.len() - length..]
+                        .iter()
+                        .collect::<PathBuf>()
+                })
+                .find(|suffix| paths.iter().filter(|other| other.ends_with(suffix)).count() == 1);
+            let value = unique_suffix.unwrap_or_else(|| path.to_path_buf());
+            (value.display().to_string(), path.to_path_buf())
+        })
+        .collect();
+    values.sort();
+    values
+}
+
+#[cfg(test)]
+mod tests;
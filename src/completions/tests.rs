@@ -0,0 +1,103 @@
+use super::*;
+use crate::directory_scanner::DirectoryType;
+
+fn entry(resolved_path: &str) -> DirectoryEntry {
+    DirectoryEntry {
+        path: PathBuf::from(resolved_path),
+        resolved_path: PathBuf::from(resolved_path),
+        display_name: resolved_path.rsplit('/').next().unwrap().to_string(),
+        entry_type: DirectoryType::Plain,
+        parent_path: None,
+    }
+}
+
+#[test]
+fn test_completion_values_use_shortest_unique_suffix() {
+    let entries = [
+        entry("/home/user/dev/api"),
+        entry("/home/user/work/api"),
+        entry("/home/user/dev/web"),
+        entry("/home/user/dev/web"),
+        entry("/srv/dev/api"),
+    ];
+
+    assert_eq!(
+        completion_values(&entries),
+        vec![
+            ("srv/dev/api".to_string(), PathBuf::from("/srv/dev/api")),
+            (
+                "user/dev/api".to_string(),
+                PathBuf::from("/home/user/dev/api")
+            ),
+            ("web".to_string(), PathBuf::from("/home/user/dev/web")),
+            ("work/api".to_string(), PathBuf::from("/home/user/work/api")),
+        ]
+    );
+}
+
+#[test]
+fn test_completion_values_fall_back_to_full_path() {
+    // `/a/b` is a suffix of `/x/a/b`, so only its full path selects it alone
+    let entries = [entry("/a/b"), entry("/x/a/b")];
+
+    assert_eq!(
+        completion_values(&entries),
+        vec![
+            ("/a/b".to_string(), PathBuf::from("/a/b")),
+            ("x/a/b".to_string(), PathBuf::from("/x/a/b")),
+        ]
+    );
+}
+
+#[test]
+fn test_write_completions_registers_binary() {
+    for (shell, name) in [
+        (CompletionShell::Bash, "bash"),
+        (CompletionShell::Zsh, "zsh"),
+        (CompletionShell::Fish, "fish"),
+        (CompletionShell::Elvish, "elvish"),
+    ] {
+        let mut script = Vec::new();
+        write_completions(shell, &mut script).unwrap();
+        let script = String::from_utf8(script).unwrap();
+        assert!(script.contains(BIN_NAME), "{shell:?}: {script}");
+        assert!(
+            script.contains(&format!("{COMPLETE_VAR}={name}"))
+                || script.contains(&format!("{COMPLETE_VAR}=\"{name}\"")),
+            "{shell:?}: {script}"
+        );
+    }
+}
+
+#[test]
+fn test_nushell_completions_complete_projects() {
+    let mut script = Vec::new();
+    write_completions(CompletionShell::Nushell, &mut script).unwrap();
+    let script = String::from_utf8(script).unwrap();
+
+    assert!(
+        script.contains("def \"nu-complete tmux-sessionizer direct_selection\" []"),
+        "{script}"
+    );
+    assert!(
+        script.contains("^tmux-sessionizer complete-projects"),
+        "{script}"
+    );
+    assert!(
+        script.contains(
+            "direct_selection?: string@\"nu-complete tmux-sessionizer direct_selection\""
+        ),
+        "{script}"
+    );
+    assert!(script.contains("export extern \"tmux-sessionizer completions\""));
+}
+
+#[test]
+fn test_write_man_page() {
+    let mut page = Vec::new();
+    write_man_page(&mut page).unwrap();
+    let page = String::from_utf8(page).unwrap();
+
+    assert!(page.contains(".TH tmux-sessionizer"), "{page}");
+    assert!(page.contains("\\-\\-search\\-path"), "{page}");
+}
@@ -3,17 +3,26 @@
 // This module defines the structure for command-line arguments using `clap`
 // and the main `Config` struct that holds the application's runtime settings.
 
-use crate::error::{ConfigError, PathValidationError};
-use crate::path_utils::expand_tilde;
-use clap::Parser;
+use crate::completions;
+use crate::directory_scanner::EntryFilter;
+use crate::error::{ConfigError, PathExpansionError, PathValidationError};
+use crate::path_utils::{PathGlob, expand_path};
+use clap::{CommandFactory, Parser, Subcommand, ValueEnum};
+use clap_complete::ArgValueCandidates;
 use cross_xdg::BaseDirs;
+use glob::Pattern;
+pub(crate) use migration::CONFIG_VERSION;
+use migration::{CURRENT_FORMAT, Deprecation, migrate_content};
 use regex::Regex;
+use schemars::JsonSchema;
 
 const APP_NAME: &str = "tmux-sessionizer";
-use serde_derive::Deserialize;
+use serde_derive::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::env;
 use std::fs;
-use std::io;
-use std::path::PathBuf;
+use std::io::{self, IsTerminal};
+use std::path::{Path, PathBuf};
 use tracing::{debug, error, info, trace, warn};
 
 /// Validates a single path to ensure it exists and is a directory.
@@ -47,57 +56,386 @@ fn validate_path_is_directory(path: &PathBuf) -> std::result::Result<(), PathVal
     }
 }
 
-/// Loads configuration from the TOML file if present.
+/// Prefix of the environment variables that override configuration file values.
+const ENV_PREFIX: &str = "TMUX_SESSIONIZER_";
+
+/// Builds a `FileConfig` from the `TMUX_SESSIONIZER_*` environment variables.
+///
+/// Every scalar and list key of the configuration file has a variable named after the key
+/// in upper case (e.g., `TMUX_SESSIONIZER_SEARCH_PATHS`). Path lists are separated by `:`,
+/// exclude patterns and globs by `,`, and booleans accept `true`/`false`, `1`/`0`, `yes`/`no` and
+/// `on`/`off`. Empty variables are ignored. Tables (`env`, `hooks`, `finder`, `profiles`)
+/// cannot be set from the environment, except `finder.border` with
+/// `TMUX_SESSIONIZER_FINDER_BORDER`.
+///
+/// # Arguments
+///
+/// * `lookup` - Returns the value of an environment variable, if set.
+fn env_file_config(lookup: impl Fn(&str) -> Option<String>) -> Result<FileConfig, ConfigError> {
+    let (file_config, errors) = collect_env_file_config(lookup);
+    first_error(file_config, errors)
+}
+
+/// Like `env_file_config`, but ignores invalid variables and returns every problem found
+/// alongside the configuration built from the valid ones.
+fn collect_env_file_config(
+    lookup: impl Fn(&str) -> Option<String>,
+) -> (FileConfig, Vec<ConfigError>) {
+    let lookup_var = |key: &str| {
+        let name = format!("{ENV_PREFIX}{key}");
+        lookup(&name)
+            .filter(|value| !value.is_empty())
+            .map(|value| (name, value))
+    };
+    // Renamed variables are still read under their deprecated names
+    let var = |key: &str| {
+        lookup_var(key).or_else(|| {
+            CURRENT_FORMAT
+                .deprecated_env_vars(key)
+                .find_map(&lookup_var)
+        })
+    };
+    let split = |value: &str, separator: char| -> Vec<String> {
+        value
+            .split(separator)
+            .filter(|item| !item.is_empty())
+            .map(str::to_string)
+            .collect()
+    };
+
+    let mut errors = Vec::new();
+
+    let session_match = match var("SESSION_MATCH") {
+        Some((_, value)) if value.eq_ignore_ascii_case("name") => Some(SessionMatch::Name),
+        Some((_, value)) if value.eq_ignore_ascii_case("path") => Some(SessionMatch::Path),
+        Some((name, value)) => {
+            errors.push(ConfigError::InvalidEnvVar {
+                name,
+                value,
+                expected: "\"name\" or \"path\"",
+            });
+            None
+        }
+        None => None,
+    };
+    let mut bool_var = |key: &str| match var(key).map(parse_env_bool) {
+        Some(Ok(value)) => Some(value),
+        Some(Err(e)) => {
+            errors.push(e);
+            None
+        }
+        None => None,
+    };
+    let direnv = bool_var("DIRENV");
+    let import_layouts = bool_var("IMPORT_LAYOUTS");
+    let finder = bool_var("FINDER_BORDER").map(|border| FinderConfig {
+        border: Some(border),
+        ..Default::default()
+    });
+
+    let file_config = FileConfig {
+        search_paths: var("SEARCH_PATHS").map(|(_, value)| split(&value, ':')),
+        additional_paths: var("ADDITIONAL_PATHS").map(|(_, value)| split(&value, ':')),
+        exclude_patterns: var("EXCLUDE_PATTERNS").map(|(_, value)| split(&value, ',')),
+        exclude_globs: var("EXCLUDE_GLOBS").map(|(_, value)| split(&value, ',')),
+        default_new_project_path: var("DEFAULT_NEW_PROJECT_PATH").map(|(_, value)| value),
+        session_match,
+        direnv,
+        import_layouts,
+        finder,
+        ..Default::default()
+    };
+    (file_config, errors)
+}
+
+/// Returns `value`, or the first of `errors` if there are any.
+fn first_error<T>(value: T, errors: Vec<ConfigError>) -> Result<T, ConfigError> {
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(value),
+    }
+}
+
+/// Parses the boolean value of the environment variable `name`.
+fn parse_env_bool((name, value): (String, String)) -> Result<bool, ConfigError> {
+    match value.to_ascii_lowercase().as_str() {
+        "true" | "1" | "yes" | "on" => Ok(true),
+        "false" | "0" | "no" | "off" => Ok(false),
+        _ => Err(ConfigError::InvalidEnvVar {
+            name,
+            value,
+            expected: "a boolean (true/false)",
+        }),
+    }
+}
+
+/// Compiles the exclude patterns of the configuration file into regular expressions.
+fn compile_exclude_patterns(patterns: Vec<String>) -> Result<Vec<Regex>, ConfigError> {
+    let mut regex_patterns = Vec::new();
+    for pattern_str in patterns {
+        match Regex::new(&pattern_str) {
+            Ok(re) => regex_patterns.push(re),
+            Err(e) => {
+                error!(pattern = %pattern_str, error = %e, "Invalid regex pattern in config file");
+                return Err(ConfigError::InvalidRegex {
+                    // Using InvalidRegex from src/error.rs
+                    pattern: pattern_str,
+                    source: e,
+                });
+            }
+        }
+    }
+    Ok(regex_patterns)
+}
+
+/// Compiles the exclude globs of the configuration into patterns, expanding `~`, `~user`
+/// and environment variables first.
+fn compile_exclude_globs(globs: Vec<String>) -> Result<Vec<Pattern>, ConfigError> {
+    globs
+        .into_iter()
+        .map(|glob| {
+            let expanded = expand_config_path(Path::new(&glob))?;
+            Pattern::new(&expanded.to_string_lossy()).map_err(|e| {
+                error!(glob = %glob, error = %e, "Invalid exclude glob in configuration");
+                ConfigError::InvalidGlob {
+                    pattern: glob,
+                    source: e,
+                }
+            })
+        })
+        .collect()
+}
+
+/// Expands `~`, `~user` and environment variables in a configured path.
+fn expand_config_path(path: &Path) -> Result<PathBuf, ConfigError> {
+    let path = path.to_string_lossy();
+    expand_path(&path).map_err(|e| {
+        error!(path = %path, error = %e, "Could not expand configured path");
+        ConfigError::PathExpansion {
+            path: path.into_owned(),
+            source: e,
+        }
+    })
+}
+
+/// Splits `paths` into literal paths and paths containing glob wildcards.
+fn split_path_globs(paths: Vec<PathBuf>) -> Result<(Vec<PathBuf>, Vec<PathGlob>), ConfigError> {
+    let mut literal_paths = Vec::new();
+    let mut globs = Vec::new();
+    for path in paths {
+        match PathGlob::new(&path) {
+            Ok(Some(glob)) => globs.push(glob),
+            Ok(None) => literal_paths.push(path),
+            Err(e) => {
+                error!(path = %path.display(), error = %e, "Invalid glob in configured path");
+                return Err(ConfigError::InvalidGlob {
+                    pattern: path.display().to_string(),
+                    source: e,
+                });
+            }
+        }
+    }
+    Ok((literal_paths, globs))
+}
+
+/// Name of the main configuration file inside the configuration directory.
+const CONFIG_FILE_NAME: &str = "tmux-sessionizer.toml";
+
+/// Name of the drop-in directory inside the configuration directory.
+/// Every `*.toml` file in it is merged on top of the main configuration file.
+const DROP_IN_DIR_NAME: &str = "conf.d";
+
+/// A single configuration file, without its includes, as one layer of the merged configuration.
+#[derive(Debug)]
+pub(crate) struct ConfigLayer {
+    /// The file the layer was loaded from.
+    pub path: PathBuf,
+    /// The parsed file, with `include` already resolved into earlier layers.
+    pub config: FileConfig,
+    /// The deprecated keys migrated while loading the file.
+    pub deprecations: Vec<Deprecation>,
+}
+
+/// Loads the configuration layers, from the file given with `--config` if any, otherwise
+/// from the default configuration directory.
 ///
 /// Path: ~/.config/tmux-sessionizer/tmux-sessionizer.toml (platform-dependent)
 ///
-/// Returns `Ok(Some(FileConfig))` if loaded and parsed successfully.
-/// Returns `Ok(None)` if the config directory is not found or the file does not exist.
-/// Returns `Err(ConfigError)` for IO errors during reading or parsing errors.
-fn load_config_file() -> Result<Option<FileConfig>, ConfigError> {
+/// Loading continues after a file fails to load, so the returned layers hold every file
+/// that could be loaded and the returned errors every problem encountered. Files listed in
+/// `include` and the drop-in files in `~/.config/tmux-sessionizer/conf.d/` are ordered as
+/// described in `collect_config_dir_layers`.
+///
+/// Returns `Err(ConfigError)` only if the config directory cannot be determined.
+pub(crate) fn collect_config_layers(
+    config_file: Option<&Path>,
+) -> Result<(Vec<ConfigLayer>, Vec<ConfigError>), ConfigError> {
+    let mut layers = Vec::new();
+    let mut errors = Vec::new();
+    if let Some(config_file) = config_file {
+        match expand_config_path(config_file) {
+            Ok(config_file) => {
+                debug!(path = %config_file.display(), "Loading configuration file given on the command line");
+                collect_file_layers(&config_file, &mut Vec::new(), &mut layers, &mut errors);
+            }
+            Err(e) => errors.push(e),
+        }
+        return Ok((layers, errors));
+    }
+
     // Use cross_xdg to find the config directory
     let Ok(base_dirs) = BaseDirs::new() else {
         // Re-use the existing error variant, the log provides the detail.
         return Err(ConfigError::CannotDetermineConfigDir);
     };
-    // Convert the &Path from config_home() into an owned PathBuf
-    let mut config_path: PathBuf = base_dirs.config_home().to_path_buf();
+    // Application-specific subdirectory
+    let config_dir = base_dirs.config_home().join(APP_NAME);
+    Ok(collect_config_dir_layers(&config_dir))
+}
 
-    // Now we can push onto the PathBuf
-    config_path.push("tmux-sessionizer"); // Application-specific subdirectory
-    config_path.push("tmux-sessionizer.toml"); // The config file itself
+/// Loads the configuration layers of `config_dir`, collecting every problem on the way.
+///
+/// Layers are returned in merge order, later layers overriding earlier ones:
+/// 1. The files listed in `include` of the main file (recursively, in list order).
+/// 2. The main file, `tmux-sessionizer.toml`.
+/// 3. The `conf.d/*.toml` drop-in files, sorted by file name (each after its own includes).
+pub(crate) fn collect_config_dir_layers(config_dir: &Path) -> (Vec<ConfigLayer>, Vec<ConfigError>) {
+    let mut layers = Vec::new();
+    let mut errors = Vec::new();
+    let config_path = config_dir.join(CONFIG_FILE_NAME);
+    if config_path.exists() {
+        collect_file_layers(&config_path, &mut Vec::new(), &mut layers, &mut errors);
+    } else {
+        trace!(path = %config_path.display(), "Main configuration file not found");
+    }
 
-    if !config_path.exists() {
-        return Ok(None);
+    match drop_in_files(&config_dir.join(DROP_IN_DIR_NAME)) {
+        Ok(drop_in_paths) => {
+            for drop_in_path in drop_in_paths {
+                debug!(path = %drop_in_path.display(), "Loading drop-in configuration file");
+                collect_file_layers(&drop_in_path, &mut Vec::new(), &mut layers, &mut errors);
+            }
+        }
+        Err(e) => errors.push(e),
     }
+    (layers, errors)
+}
 
-    let content = match fs::read_to_string(&config_path) {
-        Ok(c) => c,
+/// Merges configuration layers in order. Returns `None` if there are no layers.
+pub(crate) fn merge_layers(layers: Vec<ConfigLayer>) -> Option<FileConfig> {
+    layers
+        .into_iter()
+        .map(|layer| layer.config)
+        .reduce(FileConfig::merge)
+}
+
+/// Returns the `*.toml` files of the drop-in directory, sorted by file name.
+/// A missing directory yields no files.
+fn drop_in_files(drop_in_dir: &Path) -> Result<Vec<PathBuf>, ConfigError> {
+    let entries = match fs::read_dir(drop_in_dir) {
+        Ok(entries) => entries,
+        Err(e) if e.kind() == io::ErrorKind::NotFound => return Ok(Vec::new()),
         Err(e) => {
-            // Clone the PathBuf for the error variant
             return Err(ConfigError::FileReadError {
-                path: config_path.clone(),
+                path: drop_in_dir.to_path_buf(),
                 source: e,
             });
         }
     };
+    let mut files: Vec<PathBuf> = entries
+        .filter_map(std::result::Result::ok)
+        .map(|entry| entry.path())
+        .filter(|path| path.is_file() && path.extension().is_some_and(|ext| ext == "toml"))
+        .collect();
+    files.sort();
+    Ok(files)
+}
+
+/// Loads a configuration file as layers: first the layers of the files it includes, then
+/// the file itself.
+///
+/// Include paths may contain `~` and environment variables; relative paths are resolved against the directory of
+/// the including file. `include_stack` holds the files currently being loaded and is used
+/// to detect include cycles. Problems are pushed to `errors` and loading continues with
+/// the next file.
+fn collect_file_layers(
+    path: &Path,
+    include_stack: &mut Vec<PathBuf>,
+    layers: &mut Vec<ConfigLayer>,
+    errors: &mut Vec<ConfigError>,
+) {
+    let canonical_path = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+    if include_stack.contains(&canonical_path) {
+        error!(path = %path.display(), "Configuration include cycle detected");
+        errors.push(ConfigError::IncludeCycle {
+            path: path.to_path_buf(),
+        });
+        return;
+    }
 
-    match toml::from_str::<FileConfig>(&content) {
-        Ok(parsed_config) => Ok(Some(parsed_config)),
+    let (mut file_config, deprecations) = match parse_config_file(path) {
+        Ok(parsed) => parsed,
         Err(e) => {
-            // Clone the PathBuf for the error variant
-            Err(ConfigError::FileParseError {
-                path: config_path.clone(),
-                source: e,
-            })
+            errors.push(e);
+            return;
         }
+    };
+    include_stack.push(canonical_path);
+    let base_dir = path.parent().unwrap_or_else(|| Path::new(""));
+    for include in file_config.include.take().unwrap_or_default() {
+        let include_path = match expand_config_path(Path::new(&include)) {
+            Ok(include_path) => base_dir.join(include_path),
+            Err(e) => {
+                errors.push(e);
+                continue;
+            }
+        };
+        debug!(path = %include_path.display(), from = %path.display(), "Loading included configuration file");
+        collect_file_layers(&include_path, include_stack, layers, errors);
     }
+    include_stack.pop();
+
+    layers.push(ConfigLayer {
+        path: path.to_path_buf(),
+        config: file_config,
+        deprecations,
+    });
+}
+
+/// Reads and parses a single configuration file, without resolving its includes.
+///
+/// Files of an older format version are migrated first; the migrated deprecated keys are
+/// returned along with the configuration.
+fn parse_config_file(config_path: &Path) -> Result<(FileConfig, Vec<Deprecation>), ConfigError> {
+    let content = match fs::read_to_string(config_path) {
+        Ok(c) => c,
+        Err(e) => {
+            return Err(ConfigError::FileReadError {
+                path: config_path.to_path_buf(),
+                source: e,
+            });
+        }
+    };
+
+    let (content, deprecations) = migrate_content(&CURRENT_FORMAT, config_path, &content)?;
+    let file_config =
+        toml::from_str::<FileConfig>(&content).map_err(|e| ConfigError::FileParseError {
+            path: config_path.to_path_buf(),
+            source: e,
+        })?;
+    debug!(
+        path = %config_path.display(),
+        version = file_config.version.unwrap_or(1),
+        deprecated_keys = deprecations.len(),
+        "Parsed configuration file"
+    );
+    Ok((file_config, deprecations))
 }
 
 /// Command-line arguments parsed by clap.
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Default)]
 #[command(name = "tmux-sessionizer")]
-#[command(author, version)]
+#[command(author, version, args_conflicts_with_subcommands = true)]
 #[command(
     about = "Scans specified directories, identifies projects (including Git repositories and worktrees), and presents them via a fuzzy finder (like skim) for quick tmux session creation or switching.",
     long_about = r#"
@@ -110,31 +448,321 @@ Use the fuzzy finder (skim) to quickly select a project and jump into its tmux s
 If a session for the selected project exists, it attaches to it. Otherwise, it creates a new session.
 
 You can also provide a direct path or project name as an argument to bypass the fuzzy finder.
+
+Use `tmux-sessionizer last` to jump back to the previously used project session.
 "#
 )]
 pub(crate) struct CliArgs {
     /// Enable detailed debug logging.
-    #[arg(short, long, action = clap::ArgAction::SetTrue, help = "Enable debug logging to stderr")]
+    #[arg(short, long, global = true, action = clap::ArgAction::SetTrue, help = "Enable debug logging to stderr")]
     debug: bool,
 
     /// Initialize configuration directory and create template config file.
     #[arg(long, action = clap::ArgAction::SetTrue, help = "Initialize configuration directory and create template config file")]
     init: bool,
 
+    /// With `--init`, write the detected project directories without asking.
+    #[arg(
+        long,
+        requires = "init",
+        action = clap::ArgAction::SetTrue,
+        help = "With --init, write a config searching the detected project directories without asking"
+    )]
+    detect: bool,
+
     /// Directly select a path or name, skipping the fuzzy finder.
     #[arg(
         index = 1,
+        add = ArgValueCandidates::new(crate::completions::project_candidates),
         help = "Directly select a project by path or name, skipping the fuzzy finder",
-        long_help = "Provide a full path (e.g., /path/to/project) or a project name (e.g., my_project) to directly create or switch to its tmux session without showing the fuzzy finder interface."
+        long_help = "Provide a full path (e.g., /path/to/project) or a project name (e.g., my_project) to directly create or switch to its tmux session without showing the fuzzy finder interface. Paths to directories outside the search paths are accepted too, such as `.` for the current directory. A project named like a subcommand is selected after `--`, e.g. `tmux-sessionizer -- man`."
     )]
     direct_selection: Option<String>,
-    // #[arg(long, value_delimiter = ',', help = "Additional search paths, comma-separated")]
-    // additional_paths: Option<Vec<PathBuf>>,
+
+    /// Only select the direct selection target by an exact match.
+    #[arg(
+        long,
+        requires = "direct_selection",
+        action = clap::ArgAction::SetTrue,
+        help = "Only select the direct selection target by an exact path or name match",
+        long_help = "Only select the direct selection target by an exact path or name match. Without this flag, a target that matches no project exactly is matched fuzzily, and a target that is ambiguous or matches nothing opens the fuzzy finder with the target as query."
+    )]
+    exact: bool,
+
+    /// Create the session of the selection without switching to it.
+    #[arg(
+        long,
+        global = true,
+        action = clap::ArgAction::SetTrue,
+        help = "Create the session without switching to it, and print its name",
+        long_help = "Create the session of the selected project if it does not exist, without switching or attaching to it, and print the session name. Starts the tmux server if needed, so sessions can be prepared from scripts."
+    )]
+    detach: bool,
+
+    /// Print the selection instead of creating or switching to its session.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FORMAT",
+        value_enum,
+        num_args = 0..=1,
+        require_equals = true,
+        default_missing_value = "plain",
+        conflicts_with = "detach",
+        help = "Print the selected project instead of opening its session (plain or json)",
+        long_help = "Print the selected project instead of creating or switching to its tmux session: its path with `--print` or `--print=plain`, or a JSON object with its path, session name and type with `--print=json`. Lets shell functions use the selection for `cd`, an editor or another terminal multiplexer. The finder offers no project or worktree creation, so nothing is written to disk."
+    )]
+    print: Option<PrintFormat>,
+
+    /// Text to pre-fill the fuzzy finder query with.
+    #[arg(
+        long,
+        value_name = "TEXT",
+        conflicts_with = "direct_selection",
+        help = "Pre-fill the fuzzy finder query with this text"
+    )]
+    query: Option<String>,
+
+    /// Only offer projects of these types.
+    #[arg(
+        long = "type",
+        value_name = "TYPE",
+        value_enum,
+        help = "Only offer projects of this type (repeatable)"
+    )]
+    types: Vec<ProjectType>,
+
+    /// Only offer the worktrees of this repository.
+    #[arg(
+        long,
+        value_name = "NAME",
+        help = "Only offer the worktrees of the repository with this directory name"
+    )]
+    repo: Option<String>,
+
+    /// Only offer projects with a running session.
+    #[arg(
+        long,
+        action = clap::ArgAction::SetTrue,
+        help = "Only offer projects with a running tmux session"
+    )]
+    running: bool,
+
+    /// How the fuzzy finder lists projects, replacing `finder.view` from the configuration.
+    #[arg(
+        long,
+        value_name = "VIEW",
+        value_enum,
+        help = "List projects flat or as a tree of directories, repositories and worktrees (replaces finder.view)"
+    )]
+    view: Option<FinderView>,
+
+    /// Name of the configuration profile to use.
+    #[arg(
+        long,
+        env = "TMUX_SESSIONIZER_PROFILE",
+        global = true,
+        help = "Use the named configuration profile",
+        long_help = "Use the settings of the named [profiles.<name>] table in the configuration file on top of the global settings. Can also be set with the TMUX_SESSIONIZER_PROFILE environment variable."
+    )]
+    profile: Option<String>,
+
+    /// Configuration file to use instead of the default one.
+    #[arg(
+        long,
+        global = true,
+        value_name = "FILE",
+        env = "TMUX_SESSIONIZER_CONFIG",
+        help = "Use this configuration file instead of the default one",
+        long_help = "Load this configuration file (and the files it includes) instead of ~/.config/tmux-sessionizer/tmux-sessionizer.toml and its conf.d drop-in files. Can also be set with the TMUX_SESSIONIZER_CONFIG environment variable."
+    )]
+    config: Option<PathBuf>,
+
+    /// Search paths, replacing `search_paths` from the configuration.
+    #[arg(
+        long = "search-path",
+        global = true,
+        value_name = "PATH",
+        help = "Search the children of this directory for projects (repeatable, replaces search_paths)"
+    )]
+    search_paths: Vec<String>,
+
+    /// Additional paths, replacing `additional_paths` from the configuration.
+    #[arg(
+        long = "additional-path",
+        global = true,
+        value_name = "PATH",
+        help = "Include this directory as a project (repeatable, replaces additional_paths)"
+    )]
+    additional_paths: Vec<String>,
+
+    /// Exclude patterns, replacing `exclude_patterns` from the configuration.
+    #[arg(
+        long = "exclude",
+        global = true,
+        value_name = "REGEX",
+        help = "Exclude paths matching this regex (repeatable, replaces exclude_patterns)"
+    )]
+    exclude_patterns: Vec<String>,
+
+    /// Exclude globs, replacing `exclude_globs` from the configuration.
+    #[arg(
+        long = "exclude-glob",
+        global = true,
+        value_name = "GLOB",
+        help = "Exclude paths matching this glob (repeatable, replaces exclude_globs)"
+    )]
+    exclude_globs: Vec<String>,
+
+    /// Location for new projects, replacing `default_new_project_path` from the configuration.
+    #[arg(
+        long = "new-project-path",
+        global = true,
+        value_name = "PATH",
+        help = "Create new projects in this directory (replaces default_new_project_path)"
+    )]
+    new_project_path: Option<String>,
+    /// Optional subcommand to run instead of the project selection.
+    #[command(subcommand)]
+    command: Option<Command>,
+}
+
+impl CliArgs {
+    /// Returns the keys of the settings in `CONFIG_KEYS` that are set by these arguments.
+    fn set_keys(&self) -> Vec<&'static str> {
+        [
+            ("search_paths", !self.search_paths.is_empty()),
+            ("additional_paths", !self.additional_paths.is_empty()),
+            ("exclude_patterns", !self.exclude_patterns.is_empty()),
+            ("exclude_globs", !self.exclude_globs.is_empty()),
+            ("default_new_project_path", self.new_project_path.is_some()),
+            ("finder", self.view.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, is_set)| is_set.then_some(key))
+        .collect()
+    }
+}
+
+/// Subcommands that replace the default scan-and-select behavior.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum Command {
+    /// Switch to the most recently used project session other than the current one.
+    ///
+    /// The history of selected projects is persisted in the data directory, so this
+    /// also works after the tmux server was restarted: the session is recreated if needed.
+    Last,
+    /// Create a worktree of a repository and open it.
+    ///
+    /// Opens the finder on the Git repositories and worktrees. The selected project gets a new
+    /// worktree for the branch asked for, like with the `new-worktree` finder action.
+    Worktree,
+    /// Save the windows, panes, directories and running commands of all project sessions.
+    ///
+    /// Only sessions of projects opened through tmux-sessionizer (found in the history)
+    /// are saved. Snapshots are stored in the data directory.
+    Save,
+    /// Recreate saved project sessions that are not running.
+    ///
+    /// Without a session name, every saved session that is missing is restored.
+    Restore {
+        /// Name of the saved session to restore.
+        session: Option<String>,
+    },
+    /// Inspect the configuration without launching anything.
+    Config {
+        #[command(subcommand)]
+        action: ConfigCommand,
+    },
+    /// Print a shell completion script.
+    ///
+    /// Project names for the direct selection are completed from a scan of the configured
+    /// search paths, so they are always current.
+    Completions {
+        /// Shell to generate the script for.
+        shell: CompletionShell,
+    },
+    /// Print the man page in roff format.
+    Man,
+    /// Install tmux key bindings for tmux-sessionizer in the tmux configuration.
+    ///
+    /// The bindings open the finder in a popup, switch to the last project and open the
+    /// worktree creator in a popup. They are written between marker comments, so running
+    /// this again replaces them in place.
+    InstallKeybindings {
+        /// tmux configuration file to install the bindings in [default: ~/.tmux.conf].
+        #[arg(long, value_name = "FILE")]
+        file: Option<PathBuf>,
+        /// Print the bindings instead of installing them.
+        #[arg(long, conflicts_with_all = ["file", "dry_run"])]
+        stdout: bool,
+        /// Show what would be installed without writing anything.
+        #[arg(long)]
+        dry_run: bool,
+        /// Key (after the prefix) that opens the finder in a popup.
+        #[arg(long, value_name = "KEY", default_value = "f")]
+        finder_key: String,
+        /// Key (after the prefix) that switches to the last project.
+        #[arg(long, value_name = "KEY", default_value = "L")]
+        last_key: String,
+        /// Key (after the prefix) that opens the worktree creator in a popup.
+        #[arg(long, value_name = "KEY", default_value = "W")]
+        worktree_key: String,
+    },
+    /// Print the projects offered for completion, one `<value>\t<path>` line each.
+    #[command(hide = true)]
+    CompleteProjects,
+}
+
+/// Shells supported by the `completions` subcommand.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CompletionShell {
+    Bash,
+    Zsh,
+    Fish,
+    Elvish,
+    Nushell,
+}
+
+/// Returns the command-line definition, for generating completions and the man page.
+pub fn cli_command() -> clap::Command {
+    CliArgs::command()
+}
+
+/// Actions of the `config` subcommand.
+#[derive(Subcommand, Debug, Clone, PartialEq, Eq)]
+pub enum ConfigCommand {
+    /// Print the effective configuration and where each value comes from.
+    ///
+    /// Values are shown after merging all configuration files, environment variables,
+    /// the selected profile and command-line options, and after tilde expansion.
+    Show,
+    /// Check the configuration and report every problem found.
+    ///
+    /// Reports every unreadable or invalid file, invalid environment variable, invalid
+    /// exclude pattern and missing path at once, including those of unselected profiles.
+    Validate {
+        /// Configuration file to check instead of the default one.
+        file: Option<PathBuf>,
+    },
+    /// Rewrite configuration files in the current format, renaming deprecated keys.
+    ///
+    /// Without a file, every loaded configuration file (including includes and drop-in
+    /// files) is migrated. Each original file is kept as `<file>.bak`, or as the first free
+    /// `<file>.bak.<n>` if that exists; files already in the current format are not written.
+    Migrate {
+        /// Configuration file to migrate instead of the loaded ones.
+        file: Option<PathBuf>,
+    },
+    /// Print a JSON Schema of the configuration file.
+    ///
+    /// TOML language servers use the schema to complete and check configuration files.
+    Schema,
 }
 
 /// Represents the structure of the configuration file (e.g., tmux-sessionizer.toml).
 /// Used for deserializing the configuration from TOML format.
-#[derive(Deserialize, Debug, Default)]
+#[derive(Deserialize, JsonSchema, Debug, Default)]
 #[serde(deny_unknown_fields)] // Optional: Error if unknown fields are in the TOML
 pub(crate) struct FileConfig {
     /// Optional list of default search paths from the config file.
@@ -146,9 +774,452 @@ pub(crate) struct FileConfig {
     /// Optional list of patterns to exclude from the search.
     #[serde(default)]
     pub exclude_patterns: Option<Vec<String>>,
+    /// Optional list of glob patterns to exclude from the search.
+    #[serde(default)]
+    pub exclude_globs: Option<Vec<String>>,
     /// Optional default location where new projects should be created.
     #[serde(default)]
     pub default_new_project_path: Option<String>,
+    /// Optional strategy for finding an existing session for a selection.
+    #[serde(default)]
+    pub session_match: Option<SessionMatch>,
+    /// Optional environment variables set in every newly created session.
+    #[serde(default)]
+    pub env: Option<BTreeMap<String, String>>,
+    /// Optionally export the environment evaluated by direnv (`.envrc`) into new sessions.
+    #[serde(default)]
+    pub direnv: Option<bool>,
+    /// Optional shell hooks run around session creation and switching.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+    /// Optionally disable importing tmuxinator and tmuxp project files.
+    #[serde(default)]
+    pub import_layouts: Option<bool>,
+    /// Optional settings of the fuzzy finder.
+    #[serde(default)]
+    pub finder: Option<FinderConfig>,
+    /// Optional named profiles, selected with `--profile` or `TMUX_SESSIONIZER_PROFILE`.
+    #[serde(default)]
+    pub profiles: Option<BTreeMap<String, ProfileConfig>>,
+    /// Optional list of configuration files merged before this file.
+    #[serde(default)]
+    pub include: Option<Vec<String>>,
+    /// Optional version of the configuration format; files without it are version 1.
+    #[serde(default)]
+    pub version: Option<u32>,
+}
+
+impl FileConfig {
+    /// Merges `overrides` on top of this configuration.
+    ///
+    /// Values set in `overrides` replace the values of `self`, except for the `env` and
+    /// `profiles` tables, which are merged by key, and `hooks` and `finder`, which are merged
+    /// by setting.
+    /// The `include` list and the `version` are not merged, since includes are resolved and
+    /// older versions are migrated while loading.
+    pub(crate) fn merge(self, overrides: FileConfig) -> FileConfig {
+        FileConfig {
+            search_paths: overrides.search_paths.or(self.search_paths),
+            additional_paths: overrides.additional_paths.or(self.additional_paths),
+            exclude_patterns: overrides.exclude_patterns.or(self.exclude_patterns),
+            exclude_globs: overrides.exclude_globs.or(self.exclude_globs),
+            default_new_project_path: overrides
+                .default_new_project_path
+                .or(self.default_new_project_path),
+            session_match: overrides.session_match.or(self.session_match),
+            env: merge_tables(self.env, overrides.env),
+            direnv: overrides.direnv.or(self.direnv),
+            hooks: match (self.hooks, overrides.hooks) {
+                (Some(base), Some(hooks)) => Some(base.merged_with(hooks)),
+                (base, hooks) => hooks.or(base),
+            },
+            import_layouts: overrides.import_layouts.or(self.import_layouts),
+            finder: match (self.finder, overrides.finder) {
+                (Some(base), Some(finder)) => Some(base.merged_with(finder)),
+                (base, finder) => finder.or(base),
+            },
+            profiles: merge_tables(self.profiles, overrides.profiles),
+            include: None,
+            version: None,
+        }
+    }
+
+    /// Returns the keys of the settings in `CONFIG_KEYS` that are set in this configuration.
+    pub(crate) fn set_keys(&self) -> Vec<&'static str> {
+        [
+            ("search_paths", self.search_paths.is_some()),
+            ("additional_paths", self.additional_paths.is_some()),
+            ("exclude_patterns", self.exclude_patterns.is_some()),
+            ("exclude_globs", self.exclude_globs.is_some()),
+            (
+                "default_new_project_path",
+                self.default_new_project_path.is_some(),
+            ),
+            ("session_match", self.session_match.is_some()),
+            ("env", self.env.is_some()),
+            ("direnv", self.direnv.is_some()),
+            ("hooks", self.hooks.is_some()),
+            ("import_layouts", self.import_layouts.is_some()),
+            ("finder", self.finder.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, is_set)| is_set.then_some(key))
+        .collect()
+    }
+}
+
+/// Merges two optional tables by key, with entries of `overrides` taking precedence.
+fn merge_tables<V>(
+    base: Option<BTreeMap<String, V>>,
+    overrides: Option<BTreeMap<String, V>>,
+) -> Option<BTreeMap<String, V>> {
+    match (base, overrides) {
+        (Some(mut base), Some(overrides)) => {
+            base.extend(overrides);
+            Some(base)
+        }
+        (base, overrides) => overrides.or(base),
+    }
+}
+
+/// Settings of a named profile (`[profiles.<name>]`), applied on top of the global settings.
+///
+/// Paths, exclude patterns and the new project path replace the global values. Environment
+/// variables are added to the global `env` table and hooks replace the global hook of the
+/// same name.
+#[derive(Deserialize, JsonSchema, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ProfileConfig {
+    /// Optional search paths for this profile.
+    #[serde(default)]
+    pub search_paths: Option<Vec<String>>,
+    /// Optional additional paths for this profile.
+    #[serde(default)]
+    pub additional_paths: Option<Vec<String>>,
+    /// Optional exclude patterns for this profile.
+    #[serde(default)]
+    pub exclude_patterns: Option<Vec<String>>,
+    /// Optional exclude globs for this profile.
+    #[serde(default)]
+    pub exclude_globs: Option<Vec<String>>,
+    /// Optional location where new projects of this profile are created.
+    #[serde(default)]
+    pub default_new_project_path: Option<String>,
+    /// Optional environment variables added for sessions of this profile.
+    #[serde(default)]
+    pub env: Option<BTreeMap<String, String>>,
+    /// Optional hooks for sessions of this profile.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+}
+
+impl From<ProfileConfig> for FileConfig {
+    /// Returns the settings of `profile` as a layer to merge on top of the global
+    /// configuration, which gives profiles their semantics: values replace the global ones,
+    /// `env` variables are added and hooks replace the global hook of the same name.
+    fn from(profile: ProfileConfig) -> Self {
+        FileConfig {
+            search_paths: profile.search_paths,
+            additional_paths: profile.additional_paths,
+            exclude_patterns: profile.exclude_patterns,
+            exclude_globs: profile.exclude_globs,
+            default_new_project_path: profile.default_new_project_path,
+            env: profile.env,
+            hooks: profile.hooks,
+            ..Default::default()
+        }
+    }
+}
+
+impl ProfileConfig {
+    /// Returns the keys of the settings in `CONFIG_KEYS` that are set in this profile.
+    fn set_keys(&self) -> Vec<&'static str> {
+        [
+            ("search_paths", self.search_paths.is_some()),
+            ("additional_paths", self.additional_paths.is_some()),
+            ("exclude_patterns", self.exclude_patterns.is_some()),
+            ("exclude_globs", self.exclude_globs.is_some()),
+            (
+                "default_new_project_path",
+                self.default_new_project_path.is_some(),
+            ),
+            ("env", self.env.is_some()),
+            ("hooks", self.hooks.is_some()),
+        ]
+        .into_iter()
+        .filter_map(|(key, is_set)| is_set.then_some(key))
+        .collect()
+    }
+}
+
+/// Shell hook commands run around session creation and switching.
+///
+/// Every command may contain the placeholders `{path}`, `{session}` and `{branch}`,
+/// which are replaced with the shell-quoted project directory, session name and current
+/// Git branch (empty outside a Git repository).
+#[derive(Deserialize, JsonSchema, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct HooksConfig {
+    /// Shell command run outside tmux, in the project directory, before a session is created.
+    #[serde(default)]
+    pub pre_create: Option<String>,
+    /// Command typed into the first pane of a newly created session (e.g., `nvim .`).
+    #[serde(default)]
+    pub on_create: Option<String>,
+    /// Shell command run outside tmux, in the project directory, before switching to an existing session.
+    #[serde(default)]
+    pub on_switch: Option<String>,
+}
+
+impl HooksConfig {
+    /// Returns these hooks with every hook set in `overrides` replacing the corresponding one.
+    pub fn merged_with(&self, overrides: HooksConfig) -> HooksConfig {
+        HooksConfig {
+            pre_create: overrides.pre_create.or_else(|| self.pre_create.clone()),
+            on_create: overrides.on_create.or_else(|| self.on_create.clone()),
+            on_switch: overrides.on_switch.or_else(|| self.on_switch.clone()),
+        }
+    }
+}
+
+/// Settings of the fuzzy finder (`[finder]`).
+#[derive(Deserialize, JsonSchema, Debug, Clone, Default, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct FinderConfig {
+    /// How projects are listed: `flat` (default) or `tree`, which groups them by search path
+    /// and lists the worktrees of a repository beneath it.
+    #[serde(default)]
+    pub view: Option<FinderView>,
+    /// Prompt text, shown after the active profile (default: `Select project (or + to create new): `).
+    #[serde(default)]
+    pub prompt: Option<String>,
+    /// Height of the finder, in lines (e.g., `20`) or as a percentage of the terminal (default: `100%`).
+    #[serde(default)]
+    pub height: Option<String>,
+    /// Where the query line is: `default` puts it at the bottom, `reverse` at the top
+    /// (default: `default` for the flat view, `reverse` for the tree view).
+    #[serde(default)]
+    pub layout: Option<FinderLayout>,
+    /// Color theme in the `--color` syntax of skim, e.g. `light`, `16` or
+    /// `dark,matched:108,current_bg:236`.
+    #[serde(default)]
+    pub color: Option<String>,
+    /// Text shown above the list.
+    #[serde(default)]
+    pub header: Option<String>,
+    /// Whether a border is drawn around the finder (default: `false`).
+    #[serde(default)]
+    pub border: Option<bool>,
+    /// Badges shown before each project, for its type, language and running session:
+    /// `none` (default), `ascii`, or `nerd` for Nerd Font glyphs.
+    #[serde(default)]
+    pub icons: Option<FinderIcons>,
+    /// Keys bound to actions on the highlighted project, e.g. `ctrl-d = "kill-session"`.
+    /// Keys use the skim syntax (`ctrl-x`, `alt-x`, `f1`, ...). Bindings of several files are
+    /// merged per key.
+    #[serde(default)]
+    pub bindings: Option<BTreeMap<String, FinderAction>>,
+}
+
+impl FinderConfig {
+    /// Returns these settings with every setting of `overrides` replacing the corresponding one.
+    ///
+    /// Key bindings are merged per key, so `overrides` only replaces the keys it binds.
+    pub fn merged_with(&self, overrides: FinderConfig) -> FinderConfig {
+        let bindings = match (&self.bindings, overrides.bindings) {
+            (Some(base), Some(overrides)) => {
+                let mut merged = base.clone();
+                merged.extend(overrides);
+                Some(merged)
+            }
+            (base, overrides) => overrides.or_else(|| base.clone()),
+        };
+        FinderConfig {
+            view: overrides.view.or(self.view),
+            prompt: overrides.prompt.or_else(|| self.prompt.clone()),
+            height: overrides.height.or_else(|| self.height.clone()),
+            layout: overrides.layout.or(self.layout),
+            color: overrides.color.or_else(|| self.color.clone()),
+            header: overrides.header.or_else(|| self.header.clone()),
+            border: overrides.border.or(self.border),
+            icons: overrides.icons.or(self.icons),
+            bindings,
+        }
+    }
+}
+
+/// How the fuzzy finder lists projects.
+#[derive(Deserialize, JsonSchema, ValueEnum, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinderView {
+    /// One line per project, in scan order.
+    #[default]
+    Flat,
+    /// Projects grouped by the search path they were found in, with the worktrees of a
+    /// repository indented beneath it.
+    Tree,
+}
+
+/// Where the fuzzy finder puts its query line.
+#[derive(Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinderLayout {
+    /// Query line at the bottom, list growing upwards.
+    Default,
+    /// Query line at the top, list growing downwards.
+    Reverse,
+}
+
+/// Which badges the fuzzy finder shows before each project.
+#[derive(Deserialize, JsonSchema, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum FinderIcons {
+    /// No badges, only the project name.
+    #[default]
+    None,
+    /// Colored text badges such as `[repo]` and `rs`.
+    Ascii,
+    /// Colored Nerd Font glyphs, which need a Nerd Font in the terminal.
+    Nerd,
+}
+
+/// Action run on the highlighted project when its key is pressed in the fuzzy finder.
+#[derive(Deserialize, JsonSchema, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub enum FinderAction {
+    /// Create or switch to the session of the project, like `Enter`.
+    Open,
+    /// Create the session of the project if needed, without switching to it.
+    OpenDetached,
+    /// Ask for a branch name and create a worktree of the repository for it, then open it.
+    NewWorktree,
+    /// Kill the running session of the project.
+    KillSession,
+    /// Copy the path of the project to the tmux paste buffer and the clipboard.
+    CopyPath,
+}
+
+/// Name of the optional project-local configuration file inside a project directory.
+pub const PROJECT_CONFIG_FILE_NAME: &str = ".tmux-sessionizer.toml";
+
+/// Represents the structure of a project-local configuration file (`.tmux-sessionizer.toml`
+/// in the project directory). Settings in this file only apply to sessions for that project.
+#[derive(Deserialize, Debug, Default)]
+#[serde(deny_unknown_fields)]
+pub(crate) struct ProjectConfig {
+    /// Optional environment variables for this project's session.
+    /// These take precedence over the global `env` table and over direnv.
+    #[serde(default)]
+    pub env: Option<BTreeMap<String, String>>,
+    /// Optional hooks for this project. Each hook set here replaces the global one.
+    #[serde(default)]
+    pub hooks: Option<HooksConfig>,
+}
+
+/// Loads the project-local configuration file from `project_dir`, if present.
+///
+/// Returns `Ok(None)` if the project has no `.tmux-sessionizer.toml`.
+/// Returns `Err(ConfigError)` if the file exists but cannot be read or parsed.
+pub(crate) fn load_project_config(
+    project_dir: &Path,
+) -> Result<Option<ProjectConfig>, ConfigError> {
+    let config_path = project_dir.join(PROJECT_CONFIG_FILE_NAME);
+    if !config_path.is_file() {
+        trace!(path = %config_path.display(), "No project-local configuration file");
+        return Ok(None);
+    }
+
+    let content = fs::read_to_string(&config_path).map_err(|e| ConfigError::FileReadError {
+        path: config_path.clone(),
+        source: e,
+    })?;
+    let project_config =
+        toml::from_str::<ProjectConfig>(&content).map_err(|e| ConfigError::FileParseError {
+            path: config_path.clone(),
+            source: e,
+        })?;
+    debug!(path = %config_path.display(), ?project_config, "Loaded project-local configuration");
+    Ok(Some(project_config))
+}
+
+/// Strategy used to decide whether a selection already has a running tmux session.
+///
+/// Sessions created by hand or renamed after creation no longer carry the generated
+/// name, so they can also be found by comparing their working directory with the
+/// selected path. The strategy only controls which lookup is tried first; the other
+/// one is always used as a fallback.
+#[derive(Deserialize, JsonSchema, Debug, Clone, Copy, Default, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum SessionMatch {
+    /// Look up the generated session name first, then the session path.
+    #[default]
+    Name,
+    /// Look up the session path first, then the generated session name.
+    Path,
+}
+
+/// Project types selectable with `--type`.
+#[derive(ValueEnum, Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "lowercase")]
+pub enum ProjectType {
+    /// Git repositories, including bare repositories.
+    Git,
+    /// Linked Git worktrees.
+    Worktree,
+    /// Directories that are not Git repositories.
+    Plain,
+}
+
+/// Output formats of `--print`.
+#[derive(ValueEnum, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum PrintFormat {
+    /// The path of the selected project.
+    Plain,
+    /// A JSON object with the path, session name and type of the selected project.
+    Json,
+}
+
+/// Keys of the configuration file settings that can come from several sources.
+///
+/// Only the tests use the list, to check that the schema documents every key.
+#[cfg(test)]
+pub(crate) const CONFIG_KEYS: &[&str] = &[
+    "search_paths",
+    "additional_paths",
+    "exclude_patterns",
+    "exclude_globs",
+    "default_new_project_path",
+    "session_match",
+    "env",
+    "direnv",
+    "hooks",
+    "import_layouts",
+    "finder",
+];
+
+/// Where the effective value of a configuration setting comes from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ValueSource {
+    /// A configuration file.
+    File,
+    /// A `TMUX_SESSIONIZER_*` environment variable.
+    Environment,
+    /// The named profile.
+    Profile(String),
+    /// A command-line option.
+    CommandLine,
+}
+
+/// Returns the error for selecting the profile `name`, which is not in `profile_names`.
+fn unknown_profile(name: &str, profile_names: &[String]) -> ConfigError {
+    ConfigError::UnknownProfile {
+        name: name.to_string(),
+        available: if profile_names.is_empty() {
+            "none".to_string()
+        } else {
+            profile_names.join(", ")
+        },
+    }
 }
 
 /// Holds the application's runtime configuration.
@@ -156,18 +1227,56 @@ pub(crate) struct FileConfig {
 pub struct Config {
     /// Default directories to search for projects.
     pub search_paths: Vec<PathBuf>,
+    /// Search paths containing glob wildcards, expanded to directories when scanning.
+    pub search_globs: Vec<PathGlob>,
     /// Directory where log files will be stored.
     pub log_directory: PathBuf,
+    /// Directory where persistent application data (e.g., the session history) is stored.
+    pub data_directory: PathBuf,
     /// Additional directories specified by the user to search. (Currently unused CLI arg)
     pub additional_paths: Vec<PathBuf>,
+    /// Additional paths containing glob wildcards, expanded to directories when scanning.
+    pub additional_globs: Vec<PathGlob>,
     /// Patterns to exclude directories from the search. (Currently unused CLI arg)
     pub exclude_patterns: Vec<Regex>,
+    /// Glob patterns to exclude directories from the search.
+    pub exclude_globs: Vec<Pattern>,
     /// Flag indicating whether debug logging is enabled.
     pub debug_mode: bool,
     /// An optional path or name provided directly by the user, bypassing the fuzzy finder.
     pub direct_selection: Option<String>,
+    /// Whether the direct selection only accepts exact matches, without the fuzzy fallbacks.
+    pub exact_selection: bool,
+    /// Whether the session of the selection is created without switching to it.
+    pub detach: bool,
+    /// Format to print the selection in, instead of opening its session.
+    pub print: Option<PrintFormat>,
+    /// Text the fuzzy finder query is pre-filled with.
+    pub finder_query: Option<String>,
+    /// Criteria narrowing the scanned projects offered for selection.
+    pub entry_filter: EntryFilter,
+    /// Settings of the fuzzy finder.
+    pub finder: FinderConfig,
     /// Default directory where new projects should be created.
     pub default_new_project_path: PathBuf,
+    /// Whether existing sessions are matched by name or by working directory first.
+    pub session_match: SessionMatch,
+    /// An optional subcommand to run instead of the project selection.
+    pub command: Option<Command>,
+    /// Environment variables set in every newly created session.
+    pub env: BTreeMap<String, String>,
+    /// Whether to export the direnv-evaluated environment of a project into its new session.
+    pub direnv: bool,
+    /// Shell hooks run around session creation and switching.
+    pub hooks: HooksConfig,
+    /// Whether tmuxinator and tmuxp project files are imported as session layouts.
+    pub import_layouts: bool,
+    /// The active configuration profile, if any.
+    pub profile: Option<String>,
+    /// Names of all profiles defined in the configuration file, sorted.
+    pub profile_names: Vec<String>,
+    /// The source that last set each key of `CONFIG_KEYS`; keys not listed use the default.
+    pub sources: BTreeMap<&'static str, ValueSource>,
 }
 
 impl Default for Config {
@@ -179,12 +1288,31 @@ impl Default for Config {
 
         Config {
             search_paths: default_search_paths,
+            search_globs: Vec::new(),
             log_directory: PathBuf::new(), // Initialize, will be properly set in `build`
+            data_directory: PathBuf::new(), // Initialize, will be properly set in `build`
             additional_paths: Vec::new(),
+            additional_globs: Vec::new(),
             exclude_patterns: Vec::new(),
+            exclude_globs: Vec::new(),
             debug_mode: false,
             direct_selection: None,
+            exact_selection: false,
+            detach: false,
+            print: None,
+            finder_query: None,
+            entry_filter: EntryFilter::default(),
+            finder: FinderConfig::default(),
             default_new_project_path: PathBuf::from("~/dev"), // Default to ~/dev
+            session_match: SessionMatch::default(),
+            command: None,
+            env: BTreeMap::new(),
+            direnv: false,
+            hooks: HooksConfig::default(),
+            import_layouts: true,
+            profile: None,
+            profile_names: Vec::new(),
+            sources: BTreeMap::new(),
         }
     }
 }
@@ -199,7 +1327,24 @@ impl Config {
             // Handle initialization
             use crate::config_init::ConfigInitializer;
             let initializer = ConfigInitializer::new()?;
-            let file_was_created = initializer.init_config()?;
+            // Interactive terminals get the wizard, `--detect` the detected directories
+            // without questions, and everything else the commented template
+            let stdin = io::stdin();
+            let file_was_created = if cli_args.detect || stdin.is_terminal() {
+                let home = dirs::home_dir().ok_or_else(|| ConfigError::PathExpansion {
+                    path: "~".to_string(),
+                    source: PathExpansionError::NoHomeDirectory,
+                })?;
+                let mut input = stdin.lock();
+                let mut output = io::stdout();
+                let prompt = (!cli_args.detect).then_some((
+                    &mut input as &mut dyn io::BufRead,
+                    &mut output as &mut dyn io::Write,
+                ));
+                initializer.init_detected_config(&home, prompt)?
+            } else {
+                initializer.init_config()?
+            };
 
             // Print success message based on whether file was created or already existed
             if file_was_created {
@@ -223,21 +1368,110 @@ impl Config {
         Ok(false)
     }
 
+    /// Checks if the `config` subcommand was given and runs it if so.
+    /// Returns Ok(true) if the subcommand was run, Ok(false) if normal operation should continue.
+    ///
+    /// This runs before `new`, so that an invalid configuration can still be inspected.
+    pub fn handle_config_command_if_requested() -> Result<bool, ConfigError> {
+        let cli_args = CliArgs::parse();
+        let Some(Command::Config { action }) = cli_args.command.clone() else {
+            return Ok(false);
+        };
+
+        match action {
+            ConfigCommand::Show => print!("{}", inspect::show(cli_args)?),
+            ConfigCommand::Validate { file } => {
+                let problems = inspect::validate(&cli_args, file.as_deref())?;
+                if !problems.is_empty() {
+                    for problem in &problems {
+                        eprintln!("error: {problem}");
+                    }
+                    return Err(ConfigError::Invalid {
+                        count: problems.len(),
+                    });
+                }
+                println!("Configuration is valid.");
+            }
+            ConfigCommand::Migrate { file } => {
+                print!("{}", migration::migrate(&cli_args, file.as_deref())?);
+            }
+            ConfigCommand::Schema => print!("{}", inspect::schema()),
+        }
+
+        Ok(true)
+    }
+
+    /// Checks if the `completions`, `man` or `complete-projects` subcommand was given and runs
+    /// it if so. Returns Ok(true) if the subcommand was run, Ok(false) if normal operation
+    /// should continue.
+    ///
+    /// This runs before `new`, so that completions also work with an invalid configuration.
+    pub fn handle_completion_command_if_requested() -> io::Result<bool> {
+        let cli_args = CliArgs::parse();
+        let mut stdout = io::stdout().lock();
+        match cli_args.command {
+            Some(Command::Completions { shell }) => {
+                completions::write_completions(shell, &mut stdout)?;
+            }
+            Some(Command::Man) => completions::write_man_page(&mut stdout)?,
+            Some(Command::CompleteProjects) => completions::write_project_list(&mut stdout)?,
+            _ => return Ok(false),
+        }
+        Ok(true)
+    }
+
+    /// Loads the configuration for shell completion.
+    ///
+    /// Like `new`, but command-line arguments other than environment variables are ignored,
+    /// deprecation warnings are not printed and paths are not validated, since the result is
+    /// only used to scan for project names.
+    pub fn for_completion() -> Result<Self, ConfigError> {
+        let cli_args = CliArgs::try_parse_from([APP_NAME]).unwrap_or_default();
+        let (layers, errors) = collect_config_layers(cli_args.config.as_deref())?;
+        let file_config = merge_layers(first_error(layers, errors)?);
+        let env_config = env_file_config(|name| env::var(name).ok())?;
+        Self::build(file_config, env_config, cli_args)
+    }
+
     /// Creates a new `Config` instance by loading from file (if exists),
     /// parsing command-line arguments, and merging them.
     /// Also performs validation.
     pub fn new() -> Result<Self, ConfigError> {
-        let cli_args = CliArgs::parse();
+        Self::load(CliArgs::parse())
+    }
 
-        // Load configuration from file
-        let file_config = match load_config_file() {
-            Ok(fc) => fc,
-            Err(e) => {
-                return Err(e);
-            }
+    /// Like `new`, but uses `profile` instead of the profile selected by `--profile` or
+    /// `TMUX_SESSIONIZER_PROFILE`. `None` selects the global settings only.
+    pub fn with_profile(profile: Option<String>) -> Result<Self, ConfigError> {
+        let mut cli_args = CliArgs::parse();
+        cli_args.profile = profile;
+        Self::load(cli_args)
+    }
+
+    /// Returns the profile that follows the active one, in the order: no profile, then
+    /// every defined profile sorted by name, then no profile again.
+    pub fn next_profile(&self) -> Option<String> {
+        let next_index = match &self.profile {
+            Some(current) => self
+                .profile_names
+                .iter()
+                .position(|name| name == current)
+                .map_or(0, |index| index + 1),
+            None => 0,
         };
+        self.profile_names.get(next_index).cloned()
+    }
+
+    /// Loads the configuration file and merges it with `cli_args`, then validates the result.
+    fn load(cli_args: CliArgs) -> Result<Self, ConfigError> {
+        // Load configuration from file, stopping at the first problem
+        let (layers, errors) = collect_config_layers(cli_args.config.as_deref())?;
+        let layers = first_error(layers, errors)?;
+        migration::warn_deprecations(&layers);
+        let file_config = merge_layers(layers);
+        let env_config = env_file_config(|name| env::var(name).ok())?;
 
-        let config = Self::build(file_config, cli_args)?;
+        let config = Self::build(file_config, env_config, cli_args)?;
 
         // Validate paths after merging and expansion
         config.validate()?; // Validation logs internally using tracing, but that's okay if it happens after setup
@@ -245,19 +1479,44 @@ impl Config {
         Ok(config)
     }
 
-    /// Builds the final `Config` by merging defaults, file configuration, and CLI arguments.
+    /// Builds the final `Config` by merging defaults, file configuration, environment
+    /// variables (`env_config`, see `env_file_config`) and CLI arguments, in increasing order
+    /// of precedence. A selected profile is applied on top of the file values, below the environment.
     /// Handles path expansion and regex compilation.
-    fn build(file_config: Option<FileConfig>, cli_args: CliArgs) -> Result<Self, ConfigError> {
+    fn build(
+        file_config: Option<FileConfig>,
+        env_config: FileConfig,
+        cli_args: CliArgs,
+    ) -> Result<Self, ConfigError> {
         let defaults = Config::default();
         let mut config = Config {
             // Start with defaults. Note: search_paths from default are PathBufs with tildes.
             search_paths: defaults.search_paths,
+            search_globs: defaults.search_globs,
             additional_paths: defaults.additional_paths,
+            additional_globs: defaults.additional_globs,
             exclude_patterns: defaults.exclude_patterns, // Default is empty Vec<Regex>
+            exclude_globs: defaults.exclude_globs,
             debug_mode: defaults.debug_mode,
             direct_selection: defaults.direct_selection,
+            exact_selection: defaults.exact_selection,
+            detach: defaults.detach,
+            print: defaults.print,
+            finder_query: defaults.finder_query,
+            entry_filter: defaults.entry_filter,
+            finder: defaults.finder,
             log_directory: defaults.log_directory, // This will be set later
+            data_directory: defaults.data_directory, // This will be set later
             default_new_project_path: defaults.default_new_project_path,
+            session_match: defaults.session_match,
+            command: defaults.command,
+            env: defaults.env,
+            direnv: defaults.direnv,
+            hooks: defaults.hooks,
+            import_layouts: defaults.import_layouts,
+            profile: defaults.profile,
+            profile_names: defaults.profile_names,
+            sources: defaults.sources,
         };
 
         // Determine log directory path (early, before other processing that might log)
@@ -266,54 +1525,119 @@ impl Config {
             // This error indicates a fundamental problem finding user directories.
             return Err(ConfigError::CannotDetermineConfigDir);
         };
-        config.log_directory = xdg_base_dirs.data_home().join(APP_NAME);
+        config.data_directory = xdg_base_dirs.data_home().join(APP_NAME);
+        config.log_directory = config.data_directory.clone();
         trace!(log_dir = %config.log_directory.display(), "Determined log directory path");
 
-        // 1. Apply File Configuration (if present)
-        if let Some(fc) = file_config {
-            debug!(?fc, "Applying configuration from file");
-            if let Some(search_paths_str) = fc.search_paths {
-                config.search_paths = search_paths_str.into_iter().map(PathBuf::from).collect();
-                trace!(paths = ?config.search_paths, "Overridden search_paths from file config (pre-expansion)");
-            }
-            if let Some(additional_paths_str) = fc.additional_paths {
-                config.additional_paths = additional_paths_str
-                    .into_iter()
-                    .map(PathBuf::from)
-                    .collect();
-                trace!(paths = ?config.additional_paths, "Overridden additional_paths from file config (pre-expansion)");
-            }
-            if let Some(exclude_patterns_str) = fc.exclude_patterns {
-                let mut regex_patterns = Vec::new();
-                for pattern_str in exclude_patterns_str {
-                    match Regex::new(&pattern_str) {
-                        Ok(re) => regex_patterns.push(re),
-                        Err(e) => {
-                            error!(pattern = %pattern_str, error = %e, "Invalid regex pattern in config file");
-                            return Err(ConfigError::InvalidRegex {
-                                // Using InvalidRegex from src/error.rs
-                                pattern: pattern_str,
-                                source: e,
-                            });
-                        }
-                    }
-                }
-                config.exclude_patterns = regex_patterns;
-                trace!(
-                    count = config.exclude_patterns.len(),
-                    "Loaded exclude_patterns from file config"
+        // 1. Apply File Configuration (if present), with the selected profile on top of it and
+        //    TMUX_SESSIONIZER_* environment variables taking precedence over both
+        for key in file_config.iter().flat_map(FileConfig::set_keys) {
+            config.sources.insert(key, ValueSource::File);
+        }
+        let mut fc = match file_config {
+            Some(fc) => fc,
+            None => {
+                debug!(
+                    "No configuration file loaded or found. Using defaults combined with environment and CLI args."
                 );
+                FileConfig::default()
             }
-            if let Some(default_new_project_path_str) = fc.default_new_project_path {
-                config.default_new_project_path = PathBuf::from(default_new_project_path_str);
-                trace!(path = ?config.default_new_project_path, "Overridden default_new_project_path from file config (pre-expansion)");
+        };
+        if let Some(profiles) = &fc.profiles {
+            config.profile_names = profiles.keys().cloned().collect();
+            trace!(profiles = ?config.profile_names, "Loaded profiles from file config");
+        }
+
+        // 1b. Apply the selected profile on top of the global file configuration
+        if let Some(profile_name) = &cli_args.profile {
+            let Some(profile) = fc
+                .profiles
+                .as_mut()
+                .and_then(|profiles| profiles.remove(profile_name))
+            else {
+                error!(profile = %profile_name, "Unknown profile selected");
+                return Err(unknown_profile(profile_name, &config.profile_names));
+            };
+            debug!(profile = %profile_name, ?profile, "Applying profile");
+            for key in profile.set_keys() {
+                config
+                    .sources
+                    .insert(key, ValueSource::Profile(profile_name.clone()));
             }
-        } else {
-            debug!("No configuration file loaded or found. Using defaults combined with CLI args.");
+            fc = fc.merge(FileConfig::from(profile));
+            config.profile = Some(profile_name.clone());
+        }
+
+        for key in env_config.set_keys() {
+            config.sources.insert(key, ValueSource::Environment);
+        }
+        let fc = fc.merge(env_config);
+        debug!(
+            ?fc,
+            "Applying configuration from file, profile and environment"
+        );
+        if let Some(search_paths_str) = fc.search_paths {
+            config.search_paths = search_paths_str.into_iter().map(PathBuf::from).collect();
+            trace!(paths = ?config.search_paths, "Overridden search_paths from file config (pre-expansion)");
+        }
+        if let Some(additional_paths_str) = fc.additional_paths {
+            config.additional_paths = additional_paths_str
+                .into_iter()
+                .map(PathBuf::from)
+                .collect();
+            trace!(paths = ?config.additional_paths, "Overridden additional_paths from file config (pre-expansion)");
+        }
+        if let Some(exclude_patterns_str) = fc.exclude_patterns {
+            config.exclude_patterns = compile_exclude_patterns(exclude_patterns_str)?;
+            trace!(
+                count = config.exclude_patterns.len(),
+                "Loaded exclude_patterns from file config"
+            );
+        }
+        if let Some(exclude_globs) = fc.exclude_globs {
+            config.exclude_globs = compile_exclude_globs(exclude_globs)?;
+            trace!(
+                count = config.exclude_globs.len(),
+                "Loaded exclude_globs from file config"
+            );
+        }
+        if let Some(default_new_project_path_str) = fc.default_new_project_path {
+            config.default_new_project_path = PathBuf::from(default_new_project_path_str);
+            trace!(path = ?config.default_new_project_path, "Overridden default_new_project_path from file config (pre-expansion)");
+        }
+        if let Some(session_match) = fc.session_match {
+            config.session_match = session_match;
+            trace!(?session_match, "Overridden session_match from file config");
+        }
+        if let Some(env) = fc.env {
+            config.env = env;
+            trace!(
+                count = config.env.len(),
+                "Loaded session env from file config"
+            );
+        }
+        if let Some(direnv) = fc.direnv {
+            config.direnv = direnv;
+            trace!(direnv, "Overridden direnv from file config");
+        }
+        if let Some(hooks) = fc.hooks {
+            config.hooks = hooks;
+            trace!(hooks = ?config.hooks, "Loaded hooks from file config");
+        }
+        if let Some(import_layouts) = fc.import_layouts {
+            config.import_layouts = import_layouts;
+            trace!(import_layouts, "Overridden import_layouts from file config");
+        }
+        if let Some(finder) = fc.finder {
+            config.finder = finder;
+            trace!(finder = ?config.finder, "Loaded finder settings from file config");
         }
 
         // 2. Apply CLI Argument Overrides (Highest Precedence)
         debug!(?cli_args, "Applying CLI arguments");
+        for key in cli_args.set_keys() {
+            config.sources.insert(key, ValueSource::CommandLine);
+        }
         if cli_args.debug {
             config.debug_mode = true;
             trace!("Overridden debug_mode from CLI args");
@@ -322,45 +1646,95 @@ impl Config {
             config.direct_selection = cli_args.direct_selection;
             trace!(selection = ?config.direct_selection, "Overridden direct_selection from CLI args");
         }
-        // TODO: Add CLI overrides for search_paths, additional_paths, exclude_patterns if/when implemented in CliArgs
+        if cli_args.exact {
+            config.exact_selection = true;
+            trace!("Set exact_selection from CLI args");
+        }
+        if cli_args.detach {
+            config.detach = true;
+            trace!("Set detach from CLI args");
+        }
+        if cli_args.print.is_some() {
+            config.print = cli_args.print;
+            trace!(print = ?config.print, "Set print format from CLI args");
+        }
+        if cli_args.query.is_some() {
+            config.finder_query = cli_args.query;
+            trace!(query = ?config.finder_query, "Set finder_query from CLI args");
+        }
+        config.entry_filter = EntryFilter {
+            types: cli_args.types,
+            repo: cli_args.repo,
+            running: cli_args.running,
+        };
+        trace!(filter = ?config.entry_filter, "Set entry_filter from CLI args");
+        if cli_args.command.is_some() {
+            config.command = cli_args.command;
+            trace!(command = ?config.command, "Set subcommand from CLI args");
+        }
+        if !cli_args.search_paths.is_empty() {
+            config.search_paths = cli_args
+                .search_paths
+                .into_iter()
+                .map(PathBuf::from)
+                .collect();
+            trace!(paths = ?config.search_paths, "Overridden search_paths from CLI args (pre-expansion)");
+        }
+        if !cli_args.additional_paths.is_empty() {
+            config.additional_paths = cli_args
+                .additional_paths
+                .into_iter()
+                .map(PathBuf::from)
+                .collect();
+            trace!(paths = ?config.additional_paths, "Overridden additional_paths from CLI args (pre-expansion)");
+        }
+        if !cli_args.exclude_patterns.is_empty() {
+            config.exclude_patterns = compile_exclude_patterns(cli_args.exclude_patterns)?;
+            trace!(
+                count = config.exclude_patterns.len(),
+                "Overridden exclude_patterns from CLI args"
+            );
+        }
+        if !cli_args.exclude_globs.is_empty() {
+            config.exclude_globs = compile_exclude_globs(cli_args.exclude_globs)?;
+            trace!(
+                count = config.exclude_globs.len(),
+                "Overridden exclude_globs from CLI args"
+            );
+        }
+        if cli_args.view.is_some() {
+            config.finder.view = cli_args.view;
+            trace!(view = ?config.finder.view, "Overridden finder view from CLI args");
+        }
+        if let Some(new_project_path) = cli_args.new_project_path {
+            config.default_new_project_path = PathBuf::from(new_project_path);
+            trace!(path = ?config.default_new_project_path, "Overridden default_new_project_path from CLI args (pre-expansion)");
+        }
 
-        // 3. Post-process: Expand Tilde and Normalize Paths for all relevant path collections
-        trace!("Expanding tilde and normalizing paths for search_paths and additional_paths");
+        // 3. Post-process: Expand `~`, `~user` and environment variables in all path settings
+        trace!("Expanding search_paths and additional_paths");
         config.search_paths = config
             .search_paths
-            .into_iter()
-            .filter_map(|p| {
-                let expanded = expand_tilde(&p);
-                if expanded.is_none() && p.starts_with("~") {
-                    warn!(path = ?p.display(), "Could not expand tilde for path. It will be omitted.");
-                }
-                expanded
-            })
-            .collect();
-        trace!(expanded_search_paths = ?config.search_paths, "Search paths after tilde expansion");
+            .iter()
+            .map(|p| expand_config_path(p))
+            .collect::<Result<_, _>>()?;
+        trace!(expanded_search_paths = ?config.search_paths, "Search paths after expansion");
 
         config.additional_paths = config
             .additional_paths
-            .into_iter()
-            .filter_map(|p| {
-                let expanded = expand_tilde(&p);
-                if expanded.is_none() && p.starts_with("~") {
-                    warn!(path = ?p.display(), "Could not expand tilde for additional path. It will be omitted.");
-                }
-                expanded
-            })
-            .collect();
-        trace!(expanded_additional_paths = ?config.additional_paths, "Additional paths after tilde expansion");
-        // Note: expand_tilde logs errors internally if home dir isn't found.
-        // We filter_map to omit paths that couldn't be expanded.
+            .iter()
+            .map(|p| expand_config_path(p))
+            .collect::<Result<_, _>>()?;
+        trace!(expanded_additional_paths = ?config.additional_paths, "Additional paths after expansion");
 
-        // Expand tilde for default_new_project_path
-        if let Some(expanded) = expand_tilde(&config.default_new_project_path) {
-            config.default_new_project_path = expanded;
-        } else if config.default_new_project_path.starts_with("~") {
-            warn!(path = ?config.default_new_project_path.display(), "Could not expand tilde for default_new_project_path. Using as-is.");
-        }
-        trace!(expanded_default_new_project_path = ?config.default_new_project_path, "Default new project path after tilde expansion");
+        // Compile paths with glob wildcards once; the scanner expands them on every scan
+        (config.search_paths, config.search_globs) = split_path_globs(config.search_paths)?;
+        (config.additional_paths, config.additional_globs) =
+            split_path_globs(config.additional_paths)?;
+        trace!(search_globs = ?config.search_globs, additional_globs = ?config.additional_globs, "Compiled path globs");
+
+        config.default_new_project_path = expand_config_path(&config.default_new_project_path)?;
+        trace!(expanded_default_new_project_path = ?config.default_new_project_path, "Default new project path after expansion");
 
         Ok(config)
     }
@@ -377,5 +1751,8 @@ impl Config {
     }
 }
 
+mod inspect;
+mod migration;
+
 #[cfg(test)]
 mod tests;
@@ -0,0 +1,355 @@
+//! Implements the `config show`, `config validate` and `config schema` subcommands.
+
+use super::{
+    CliArgs, Config, ConfigError, ENV_PREFIX, FileConfig, FinderAction, FinderConfig, FinderIcons,
+    FinderLayout, FinderView, SessionMatch, ValueSource, collect_config_layers,
+    collect_env_file_config, env_file_config, expand_config_path, first_error, merge_layers,
+    migration, unknown_profile, validate_path_is_directory,
+};
+use crate::path_utils::PathGlob;
+use glob::Pattern;
+use regex::Regex;
+use schemars::generate::SchemaSettings;
+use std::collections::{BTreeMap, BTreeSet};
+use std::env;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Loads the effective configuration and renders it for `config show`.
+///
+/// Loading stops at the first problem, like a normal start, but paths are not checked for
+/// existence, so a configuration pointing at missing directories can still be inspected.
+pub(super) fn show(cli_args: CliArgs) -> Result<String, ConfigError> {
+    let (layers, errors) = collect_config_layers(cli_args.config.as_deref())?;
+    let layers = first_error(layers, errors)?;
+    migration::warn_deprecations(&layers);
+
+    let files: Vec<PathBuf> = layers.iter().map(|layer| layer.path.clone()).collect();
+    let mut file_sources = BTreeMap::new();
+    for layer in &layers {
+        for key in layer.config.set_keys() {
+            file_sources.insert(key, layer.path.clone());
+        }
+    }
+
+    let env_config = env_file_config(|name| env::var(name).ok())?;
+    let config = Config::build(merge_layers(layers), env_config, cli_args)?;
+    Ok(render(&config, &files, &file_sources))
+}
+
+/// Renders `config` as TOML, with the source of every value as a trailing comment.
+///
+/// # Arguments
+///
+/// * `files` - The loaded configuration files, in merge order.
+/// * `file_sources` - For each key set by a file, the last file that set it.
+pub(super) fn render(
+    config: &Config,
+    files: &[PathBuf],
+    file_sources: &BTreeMap<&str, PathBuf>,
+) -> String {
+    let source = |key: &str| match config.sources.get(key) {
+        None => "default".to_string(),
+        Some(ValueSource::File) => file_sources.get(key).map_or_else(
+            || "configuration file".to_string(),
+            |path| path.display().to_string(),
+        ),
+        Some(ValueSource::Environment) => format!("{ENV_PREFIX}{}", key.to_ascii_uppercase()),
+        Some(ValueSource::Profile(name)) => format!("profile \"{name}\""),
+        Some(ValueSource::CommandLine) => "command line".to_string(),
+    };
+    let strings = |values: Vec<String>| {
+        toml::Value::Array(values.into_iter().map(toml::Value::String).collect())
+    };
+    let paths = |paths: &[PathBuf], globs: &[PathGlob]| {
+        strings(
+            paths
+                .iter()
+                .map(|path| path.display().to_string())
+                .chain(globs.iter().map(|glob| glob.as_str().to_string()))
+                .collect(),
+        )
+    };
+    let string_table = |entries: Vec<(&str, &String)>| {
+        toml::Value::Table(
+            entries
+                .into_iter()
+                .map(|(key, value)| (key.to_string(), toml::Value::String(value.clone())))
+                .collect(),
+        )
+    };
+
+    let mut lines = Vec::new();
+    if files.is_empty() {
+        lines.push("# No configuration file loaded".to_string());
+    } else {
+        lines.push("# Configuration files, in merge order:".to_string());
+        lines.extend(files.iter().map(|file| format!("#   {}", file.display())));
+    }
+    lines.push(format!(
+        "# Profile: {}",
+        config.profile.as_deref().unwrap_or("none")
+    ));
+    lines.push(format!(
+        "# Data directory: {}",
+        config.data_directory.display()
+    ));
+    lines.push(String::new());
+
+    let hooks = &config.hooks;
+    let values = [
+        (
+            "search_paths",
+            paths(&config.search_paths, &config.search_globs),
+        ),
+        (
+            "additional_paths",
+            paths(&config.additional_paths, &config.additional_globs),
+        ),
+        (
+            "exclude_patterns",
+            strings(
+                config
+                    .exclude_patterns
+                    .iter()
+                    .map(|pattern| pattern.as_str().to_string())
+                    .collect(),
+            ),
+        ),
+        (
+            "exclude_globs",
+            strings(
+                config
+                    .exclude_globs
+                    .iter()
+                    .map(|glob| glob.as_str().to_string())
+                    .collect(),
+            ),
+        ),
+        (
+            "default_new_project_path",
+            toml::Value::String(config.default_new_project_path.display().to_string()),
+        ),
+        (
+            "session_match",
+            toml::Value::String(
+                match config.session_match {
+                    SessionMatch::Name => "name",
+                    SessionMatch::Path => "path",
+                }
+                .to_string(),
+            ),
+        ),
+        (
+            "env",
+            string_table(config.env.iter().map(|(k, v)| (k.as_str(), v)).collect()),
+        ),
+        ("direnv", toml::Value::Boolean(config.direnv)),
+        (
+            "hooks",
+            string_table(
+                [
+                    ("pre_create", &hooks.pre_create),
+                    ("on_create", &hooks.on_create),
+                    ("on_switch", &hooks.on_switch),
+                ]
+                .into_iter()
+                .filter_map(|(name, hook)| hook.as_ref().map(|hook| (name, hook)))
+                .collect(),
+            ),
+        ),
+        (
+            "import_layouts",
+            toml::Value::Boolean(config.import_layouts),
+        ),
+        ("finder", finder_table(&config.finder)),
+    ];
+    for (key, value) in values {
+        lines.push(format!("{key} = {value}  # {}", source(key)));
+    }
+
+    lines.join("\n") + "\n"
+}
+
+/// Returns the `[finder]` settings that are set, as a TOML table.
+fn finder_table(finder: &FinderConfig) -> toml::Value {
+    let mut table: toml::Table = [
+        (
+            "view",
+            finder.view.map(|view| match view {
+                FinderView::Flat => "flat",
+                FinderView::Tree => "tree",
+            }),
+        ),
+        ("prompt", finder.prompt.as_deref()),
+        ("height", finder.height.as_deref()),
+        (
+            "layout",
+            finder.layout.map(|layout| match layout {
+                FinderLayout::Default => "default",
+                FinderLayout::Reverse => "reverse",
+            }),
+        ),
+        ("color", finder.color.as_deref()),
+        ("header", finder.header.as_deref()),
+        (
+            "icons",
+            finder.icons.map(|icons| match icons {
+                FinderIcons::None => "none",
+                FinderIcons::Ascii => "ascii",
+                FinderIcons::Nerd => "nerd",
+            }),
+        ),
+    ]
+    .into_iter()
+    .filter_map(|(name, value)| {
+        value.map(|value| (name.to_string(), toml::Value::String(value.to_string())))
+    })
+    .collect();
+    if let Some(bindings) = &finder.bindings {
+        let bindings = bindings
+            .iter()
+            .map(|(key, action)| {
+                let action = match action {
+                    FinderAction::Open => "open",
+                    FinderAction::OpenDetached => "open-detached",
+                    FinderAction::NewWorktree => "new-worktree",
+                    FinderAction::KillSession => "kill-session",
+                    FinderAction::CopyPath => "copy-path",
+                };
+                (key.clone(), toml::Value::String(action.to_string()))
+            })
+            .collect();
+        table.insert("bindings".to_string(), toml::Value::Table(bindings));
+    }
+    if let Some(border) = finder.border {
+        table.insert("border".to_string(), toml::Value::Boolean(border));
+    }
+    toml::Value::Table(table)
+}
+
+/// Checks the configuration for `config validate` and returns every problem found.
+///
+/// Unlike a normal start, loading continues after a problem, and the exclude patterns and
+/// paths of every profile are checked, not only those of the selected profile.
+///
+/// # Arguments
+///
+/// * `cli_args` - The command-line arguments, whose options are checked as well.
+/// * `file` - Configuration file to check instead of the one `cli_args` selects.
+pub(super) fn validate(
+    cli_args: &CliArgs,
+    file: Option<&Path>,
+) -> Result<Vec<ConfigError>, ConfigError> {
+    let (layers, mut problems) = collect_config_layers(file.or(cli_args.config.as_deref()))?;
+    migration::warn_deprecations(&layers);
+    let (env_config, env_problems) = collect_env_file_config(|name| env::var(name).ok());
+    problems.extend(env_problems);
+
+    let file_config = merge_layers(layers).unwrap_or_default().merge(env_config);
+    problems.extend(check_settings(&file_config, cli_args));
+    debug!(count = problems.len(), "Configuration validated");
+    Ok(problems)
+}
+
+/// Checks the profile selection, exclude patterns, exclude globs and paths of the merged file and
+/// environment configuration, its profiles and the command-line options.
+pub(super) fn check_settings(file_config: &FileConfig, cli_args: &CliArgs) -> Vec<ConfigError> {
+    let mut problems = Vec::new();
+    let profiles = file_config.profiles.as_ref();
+
+    if let Some(name) = &cli_args.profile
+        && !profiles.is_some_and(|profiles| profiles.contains_key(name))
+    {
+        let profile_names: Vec<String> = profiles
+            .map(|profiles| profiles.keys().cloned().collect())
+            .unwrap_or_default();
+        problems.push(unknown_profile(name, &profile_names));
+    }
+
+    let mut patterns = BTreeSet::new();
+    let mut globs = BTreeSet::new();
+    let mut paths = BTreeSet::new();
+    let mut add_settings = |exclude_patterns: &Option<Vec<String>>,
+                            exclude_globs: &Option<Vec<String>>,
+                            path_lists: [&Option<Vec<String>>; 2]| {
+        patterns.extend(exclude_patterns.iter().flatten().cloned());
+        globs.extend(exclude_globs.iter().flatten().cloned());
+        paths.extend(path_lists.into_iter().flatten().flatten().cloned());
+    };
+    add_settings(
+        &file_config.exclude_patterns,
+        &file_config.exclude_globs,
+        [&file_config.search_paths, &file_config.additional_paths],
+    );
+    for profile in profiles.into_iter().flat_map(BTreeMap::values) {
+        add_settings(
+            &profile.exclude_patterns,
+            &profile.exclude_globs,
+            [&profile.search_paths, &profile.additional_paths],
+        );
+    }
+    patterns.extend(cli_args.exclude_patterns.iter().cloned());
+    globs.extend(cli_args.exclude_globs.iter().cloned());
+    paths.extend(cli_args.search_paths.iter().cloned());
+    paths.extend(cli_args.additional_paths.iter().cloned());
+
+    for pattern in patterns {
+        if let Err(e) = Regex::new(&pattern) {
+            problems.push(ConfigError::InvalidRegex { pattern, source: e });
+        }
+    }
+    for glob in globs {
+        match expand_config_path(Path::new(&glob)) {
+            Ok(expanded) => {
+                if let Err(e) = Pattern::new(&expanded.to_string_lossy()) {
+                    problems.push(ConfigError::InvalidGlob {
+                        pattern: glob,
+                        source: e,
+                    });
+                }
+            }
+            Err(e) => problems.push(e),
+        }
+    }
+    let mut expanded_paths = BTreeSet::new();
+    for path in paths {
+        match expand_config_path(Path::new(&path)) {
+            Ok(expanded) => {
+                expanded_paths.insert(expanded);
+            }
+            Err(e) => problems.push(e),
+        }
+    }
+    for path in expanded_paths {
+        // Globs may match nothing yet, so only their syntax is checked
+        let result = match PathGlob::new(&path) {
+            Ok(Some(_)) => Ok(()),
+            Ok(None) => validate_path_is_directory(&path).map_err(ConfigError::from),
+            Err(e) => Err(ConfigError::InvalidGlob {
+                pattern: path.display().to_string(),
+                source: e,
+            }),
+        };
+        if let Err(e) = result {
+            problems.push(e);
+        }
+    }
+    problems
+}
+
+/// Generates the JSON Schema of the configuration file for `config schema`.
+///
+/// The schema uses draft 7, which TOML language servers such as Taplo support, and is
+/// derived from `FileConfig`, so it always matches what the configuration loader accepts.
+pub(super) fn schema() -> String {
+    let mut schema = SchemaSettings::draft07()
+        .into_generator()
+        .into_root_schema_for::<FileConfig>();
+    schema.insert("title".to_string(), "tmux-sessionizer configuration".into());
+    schema.insert(
+        "description".to_string(),
+        "Configuration file of tmux-sessionizer (`tmux-sessionizer.toml`).".into(),
+    );
+    serde_json::to_string_pretty(&schema).expect("JSON schemas always serialize") + "\n"
+}
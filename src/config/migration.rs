@@ -0,0 +1,420 @@
+//! Migrates configuration files written for older versions of the configuration format.
+//!
+//! Files without a `version` key are version 1. Files of an older version are migrated in
+//! memory whenever they are loaded, with a deprecation warning for every renamed key, and
+//! rewritten in the current format by the `config migrate` subcommand.
+
+use super::{
+    CliArgs, ConfigError, ConfigLayer, ENV_PREFIX, FileConfig, collect_config_layers, first_error,
+};
+use std::borrow::Cow;
+use std::ffi::OsString;
+use std::fmt;
+use std::fs;
+use std::io::{self, Write};
+use std::path::{Path, PathBuf};
+use toml_edit::{DocumentMut, Item, Key, TableLike, value};
+use tracing::{debug, info, warn};
+
+/// The current version of the configuration format.
+pub(crate) const CONFIG_VERSION: u32 = 1;
+
+/// Keys renamed by each version, as `(version, old key, new key)`.
+///
+/// No key has been renamed yet. Renaming a key adds it here and increments `CONFIG_VERSION`.
+const RENAMED_KEYS: &[(u32, &str, &str)] = &[];
+
+/// Environment variables renamed along with their keys, as `(old, new)`.
+const RENAMED_ENV_VARS: &[(&str, &str)] = &[];
+
+/// The current configuration format.
+pub(crate) const CURRENT_FORMAT: Format = Format {
+    version: CONFIG_VERSION,
+    renamed_keys: RENAMED_KEYS,
+    renamed_env_vars: RENAMED_ENV_VARS,
+};
+
+/// A version of the configuration format, with the renames that lead up to it.
+#[derive(Debug)]
+pub(crate) struct Format {
+    /// The version of the format.
+    pub version: u32,
+    /// Keys renamed by each version, as `(version, old key, new key)`.
+    ///
+    /// Renames apply to the top level of a file and to every `[profiles.<name>]` table.
+    pub renamed_keys: &'static [(u32, &'static str, &'static str)],
+    /// Environment variables renamed along with their keys, as `(old, new)`, without the
+    /// `TMUX_SESSIONIZER_` prefix.
+    pub renamed_env_vars: &'static [(&'static str, &'static str)],
+}
+
+impl Format {
+    /// Returns the deprecated names of the environment variable `name`, without the prefix.
+    pub(crate) fn deprecated_env_vars<'a>(
+        &self,
+        name: &'a str,
+    ) -> impl Iterator<Item = &'static str> + 'a {
+        self.renamed_env_vars
+            .iter()
+            .filter(move |(_, new)| *new == name)
+            .map(|(old, _)| *old)
+    }
+}
+
+/// A deprecated key found in a configuration file, or a deprecated environment variable.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub(crate) struct Deprecation {
+    /// The deprecated key (e.g., `profiles.work.<old key>`).
+    pub key: String,
+    /// The key that replaces it.
+    pub replacement: String,
+    /// Whether the deprecated key is ignored, because its replacement is set as well.
+    pub ignored: bool,
+}
+
+impl fmt::Display for Deprecation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.ignored {
+            write!(
+                f,
+                "`{}` is deprecated and ignored, because `{}` is set as well",
+                self.key, self.replacement
+            )
+        } else {
+            write!(
+                f,
+                "`{}` is deprecated, use `{}` instead",
+                self.key, self.replacement
+            )
+        }
+    }
+}
+
+/// Migrates the content of the configuration file `path` to `format`, in memory.
+///
+/// Returns the content unchanged if there is nothing to migrate, or if it is not valid TOML,
+/// which is reported when the content is deserialized.
+///
+/// # Errors
+///
+/// Returns `ConfigError::UnsupportedVersion` if the file is newer than `format`.
+pub(crate) fn migrate_content<'a>(
+    format: &Format,
+    path: &Path,
+    content: &'a str,
+) -> Result<(Cow<'a, str>, Vec<Deprecation>), ConfigError> {
+    let Ok(mut document) = content.parse::<DocumentMut>() else {
+        return Ok((Cow::Borrowed(content), Vec::new()));
+    };
+    let deprecations = migrate_document(format, path, &mut document)?;
+    if deprecations.is_empty() {
+        Ok((Cow::Borrowed(content), deprecations))
+    } else {
+        Ok((Cow::Owned(document.to_string()), deprecations))
+    }
+}
+
+/// Applies the migrations of every version of `format` newer than the version of `document`.
+fn migrate_document(
+    format: &Format,
+    path: &Path,
+    document: &mut DocumentMut,
+) -> Result<Vec<Deprecation>, ConfigError> {
+    let version = document_version(format, path, document)?;
+    let mut deprecations = Vec::new();
+    for &(_, old, new) in format
+        .renamed_keys
+        .iter()
+        .filter(|(since, ..)| *since > version)
+    {
+        rename_key(document.as_table_mut(), "", old, new, &mut deprecations);
+        let Some(profiles) = document
+            .get_mut("profiles")
+            .and_then(Item::as_table_like_mut)
+        else {
+            continue;
+        };
+        for (name, profile) in profiles.iter_mut() {
+            if let Some(profile) = profile.as_table_like_mut() {
+                let prefix = format!("profiles.{}.", name.get());
+                rename_key(profile, &prefix, old, new, &mut deprecations);
+            }
+        }
+    }
+    Ok(deprecations)
+}
+
+/// Returns the format version of `document`, which is 1 if it has no `version` key.
+///
+/// A `version` that is not an integer is left to deserialization to report.
+fn document_version(
+    format: &Format,
+    path: &Path,
+    document: &DocumentMut,
+) -> Result<u32, ConfigError> {
+    let Some(version) = document.get("version").and_then(Item::as_integer) else {
+        return Ok(1);
+    };
+    u32::try_from(version)
+        .ok()
+        .filter(|version| (1..=format.version).contains(version))
+        .ok_or_else(|| ConfigError::UnsupportedVersion {
+            path: path.to_path_buf(),
+            version,
+            supported: format.version,
+        })
+}
+
+/// Renames the key `old` of `table` to `new` in place, keeping its comments and position.
+///
+/// If `new` is set as well, `old` is removed instead. `prefix` is the dotted path of `table`,
+/// used to name the key in the recorded deprecation.
+fn rename_key(
+    table: &mut dyn TableLike,
+    prefix: &str,
+    old: &str,
+    new: &str,
+    deprecations: &mut Vec<Deprecation>,
+) {
+    if !table.contains_key(old) {
+        return;
+    }
+    let ignored = table.contains_key(new);
+    deprecations.push(Deprecation {
+        key: format!("{prefix}{old}"),
+        replacement: format!("{prefix}{new}"),
+        ignored,
+    });
+    if ignored {
+        table.remove(old);
+        return;
+    }
+
+    // Tables cannot rename a key, so every entry is reinserted to keep the order
+    let entries = table_entries(table);
+    table.clear();
+    for (key, item) in entries {
+        let key = if key.get() == old {
+            Key::new(new)
+                .with_leaf_decor(key.leaf_decor().clone())
+                .with_dotted_decor(key.dotted_decor().clone())
+        } else {
+            key
+        };
+        table.entry_format(&key).or_insert(item);
+    }
+}
+
+/// Returns copies of the entries of `table`, in order and with their formatting.
+fn table_entries(table: &dyn TableLike) -> Vec<(Key, Item)> {
+    table
+        .iter()
+        .filter_map(|(name, _)| table.get_key_value(name))
+        .map(|(key, item)| (key.clone(), item.clone()))
+        .collect()
+}
+
+/// Returns the deprecation warnings for the keys of `layers` and the environment variables
+/// renamed by `format` found with `lookup`.
+pub(crate) fn deprecation_warnings(
+    format: &Format,
+    layers: &[ConfigLayer],
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Vec<String> {
+    let mut warnings: Vec<String> = layers
+        .iter()
+        .flat_map(|layer| {
+            layer
+                .deprecations
+                .iter()
+                .map(|deprecation| format!("{}: {deprecation}", layer.path.display()))
+        })
+        .collect();
+    if !warnings.is_empty() {
+        warnings.push(
+            "run `tmux-sessionizer config migrate` to update the configuration files".to_string(),
+        );
+    }
+
+    let is_set = |name: &str| lookup(name).is_some_and(|value| !value.is_empty());
+    for (old, new) in format.renamed_env_vars {
+        let (old, new) = (format!("{ENV_PREFIX}{old}"), format!("{ENV_PREFIX}{new}"));
+        if is_set(&old) {
+            let deprecation = Deprecation {
+                ignored: is_set(&new),
+                key: old,
+                replacement: new,
+            };
+            warnings.push(format!("environment: {deprecation}"));
+        }
+    }
+    warnings
+}
+
+/// Prints a warning on stderr for every deprecated key of `layers` or the environment.
+pub(crate) fn warn_deprecations(layers: &[ConfigLayer]) {
+    for warning in deprecation_warnings(&CURRENT_FORMAT, layers, |name| std::env::var(name).ok()) {
+        warn!(%warning, "Deprecated configuration");
+        eprintln!("warning: {warning}");
+    }
+}
+
+/// A configuration file rewritten by `migrate_file`.
+#[derive(Debug)]
+pub(crate) struct MigratedFile {
+    /// The copy of the original file.
+    pub backup: PathBuf,
+    /// The version the file was migrated from.
+    pub from_version: u32,
+    /// The deprecated keys that were renamed or removed.
+    pub deprecations: Vec<Deprecation>,
+}
+
+/// Rewrites the configuration file `path` in `format`, keeping its comments and formatting,
+/// after copying the original to `<path>.bak`. An existing backup is never replaced: the
+/// copy goes to the first free `<path>.bak.<n>` instead.
+///
+/// Returns `Ok(None)` without writing anything if the file is already in `format`.
+///
+/// # Errors
+///
+/// Returns a `ConfigError` if the file cannot be read, parsed or written, if it is newer than
+/// `format`, or if the migrated file would not load.
+pub(crate) fn migrate_file(
+    format: &Format,
+    path: &Path,
+) -> Result<Option<MigratedFile>, ConfigError> {
+    let content = fs::read_to_string(path).map_err(|e| ConfigError::FileReadError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    let mut document =
+        content
+            .parse::<DocumentMut>()
+            .map_err(|e| ConfigError::MigrationParseError {
+                path: path.to_path_buf(),
+                source: Box::new(e),
+            })?;
+    let from_version = document_version(format, path, &document)?;
+    if from_version == format.version {
+        debug!(path = %path.display(), "Configuration file is already in the current format");
+        return Ok(None);
+    }
+
+    let deprecations = migrate_document(format, path, &mut document)?;
+    set_version(&mut document, format.version);
+    let migrated = document.to_string();
+    // Never replace a loadable file with one that does not load
+    toml::from_str::<FileConfig>(&migrated).map_err(|e| ConfigError::FileParseError {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+
+    let backup = write_backup(path, &content)?;
+    fs::write(path, migrated).map_err(|e| ConfigError::MigrationWriteFailed {
+        path: path.to_path_buf(),
+        source: e,
+    })?;
+    info!(path = %path.display(), backup = %backup.display(), from_version, "Migrated configuration file");
+
+    Ok(Some(MigratedFile {
+        backup,
+        from_version,
+        deprecations,
+    }))
+}
+
+/// Writes `content` to a new backup file of `path`, `<path>.bak` or the first free
+/// `<path>.bak.<n>`, and returns the path of the backup.
+fn write_backup(path: &Path, content: &str) -> Result<PathBuf, ConfigError> {
+    for attempt in 0.. {
+        let mut backup = OsString::from(path.as_os_str());
+        backup.push(".bak");
+        if attempt > 0 {
+            backup.push(format!(".{attempt}"));
+        }
+        let backup = PathBuf::from(backup);
+        // `create_new` fails instead of replacing a backup that exists already
+        let written = fs::OpenOptions::new()
+            .write(true)
+            .create_new(true)
+            .open(&backup)
+            .and_then(|mut file| file.write_all(content.as_bytes()));
+        match written {
+            Ok(()) => return Ok(backup),
+            Err(e) if e.kind() == io::ErrorKind::AlreadyExists => {
+                debug!(backup = %backup.display(), "Backup file exists already, trying the next name");
+            }
+            Err(e) => {
+                return Err(ConfigError::MigrationWriteFailed {
+                    path: backup,
+                    source: e,
+                });
+            }
+        }
+    }
+    unreachable!("the backup attempts are unbounded")
+}
+
+/// Sets the `version` of `document` to `version`, as its first key.
+fn set_version(document: &mut DocumentMut, version: u32) {
+    if let Some(item) = document.get_mut("version") {
+        *item = value(i64::from(version));
+        return;
+    }
+    let entries = table_entries(document.as_table());
+    document.as_table_mut().clear();
+    document.insert("version", value(i64::from(version)));
+    for (key, item) in entries {
+        document.as_table_mut().entry_format(&key).or_insert(item);
+    }
+}
+
+/// Migrates `file`, or every loaded configuration file, for `config migrate`, and returns
+/// a report of the changes.
+pub(super) fn migrate(cli_args: &CliArgs, file: Option<&Path>) -> Result<String, ConfigError> {
+    let paths = if let Some(file) = file {
+        vec![file.to_path_buf()]
+    } else {
+        let (layers, errors) = collect_config_layers(cli_args.config.as_deref())?;
+        first_error(layers, errors)?
+            .into_iter()
+            .map(|layer| layer.path)
+            .collect()
+    };
+    if paths.is_empty() {
+        return Ok("No configuration file to migrate.\n".to_string());
+    }
+
+    let mut lines = Vec::new();
+    for path in paths {
+        match migrate_file(&CURRENT_FORMAT, &path)? {
+            Some(migrated) => {
+                lines.push(format!(
+                    "Migrated {} from version {} to {CONFIG_VERSION} (backup: {})",
+                    path.display(),
+                    migrated.from_version,
+                    migrated.backup.display()
+                ));
+                for deprecation in migrated.deprecations {
+                    lines.push(if deprecation.ignored {
+                        format!(
+                            "  removed `{}`, since `{}` is set",
+                            deprecation.key, deprecation.replacement
+                        )
+                    } else {
+                        format!(
+                            "  renamed `{}` to `{}`",
+                            deprecation.key, deprecation.replacement
+                        )
+                    });
+                }
+            }
+            None => lines.push(format!(
+                "{} is already current (version {CONFIG_VERSION}), nothing to migrate",
+                path.display()
+            )),
+        }
+    }
+    Ok(lines.join("\n") + "\n")
+}
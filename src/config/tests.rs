@@ -28,9 +28,10 @@ fn test_build_with_cli_only_no_file_config() {
         debug: true,
         init: false,
         direct_selection: Some("my_project_cli".to_string()),
+        ..Default::default()
     };
     // Pass None for file_config
-    let config = Config::build(None, cli_args).expect("Config build failed");
+    let config = Config::build(None, FileConfig::default(), cli_args).expect("Config build failed");
 
     assert!(config.debug_mode);
     assert_eq!(config.direct_selection, Some("my_project_cli".to_string()));
@@ -57,16 +58,18 @@ fn test_build_with_file_config_overrides_defaults_no_cli_override() {
         search_paths: Some(vec!["/etc/from_file".to_string(), "~/file_dev".to_string()]),
         additional_paths: Some(vec!["/var/log/from_file".to_string()]),
         exclude_patterns: Some(vec!["^\\.git$".to_string(), "target/".to_string()]),
-        default_new_project_path: None,
+        ..Default::default()
     };
     let cli_args = CliArgs {
         // CLI args that don't override file config for these fields
         debug: false,
         init: false,
         direct_selection: None,
+        ..Default::default()
     };
 
-    let config = Config::build(Some(file_config_content), cli_args).expect("Config build failed");
+    let config = Config::build(Some(file_config_content), FileConfig::default(), cli_args)
+        .expect("Config build failed");
 
     assert!(!config.debug_mode); // From CLI (or default if CLI didn't set)
     assert_eq!(config.direct_selection, None); // From CLI (or default)
@@ -99,18 +102,20 @@ fn test_build_cli_overrides_file_config_and_defaults() {
         search_paths: Some(vec!["/file/path_search".to_string()]), // Will be overridden by default if CLI for paths is not implemented
         additional_paths: Some(vec!["/file/path_add".to_string()]), // Same
         exclude_patterns: Some(vec!["file_pattern".to_string()]),  // Same
-        default_new_project_path: None,
+        ..Default::default()
     };
     let cli_args = CliArgs {
         debug: true, // CLI overrides default false and any file setting (if file had debug)
         init: false,
         direct_selection: Some("cli_selected_project".to_string()), // CLI overrides default None and file
+        ..Default::default()
     };
     // Note: Current CliArgs doesn't have fields for paths/patterns.
     // So, file paths/patterns will take precedence over defaults if present.
     // If CLI args for these are added later, this test would need adjustment.
 
-    let config = Config::build(Some(file_config_content), cli_args).expect("Config build failed");
+    let config = Config::build(Some(file_config_content), FileConfig::default(), cli_args)
+        .expect("Config build failed");
 
     assert!(config.debug_mode); // From CLI
     assert_eq!(
@@ -137,15 +142,20 @@ fn test_build_invalid_regex_in_file_config_returns_error() {
         search_paths: None,
         additional_paths: None,
         exclude_patterns: Some(vec!["[invalidRegex".to_string()]), // This is an invalid regex
-        default_new_project_path: None,
+        ..Default::default()
     };
     let cli_args = CliArgs {
         debug: false,
         init: false,
         direct_selection: None,
+        ..Default::default()
     };
 
-    let result = Config::build(Some(file_config_with_bad_regex), cli_args);
+    let result = Config::build(
+        Some(file_config_with_bad_regex),
+        FileConfig::default(),
+        cli_args,
+    );
     assert!(result.is_err());
     match result.err().unwrap() {
         ConfigError::InvalidRegex { pattern, .. } => {
@@ -162,9 +172,11 @@ fn test_build_empty_file_config_uses_defaults_and_cli() {
         debug: true,
         init: false,
         direct_selection: Some("cli_only_project".to_string()),
+        ..Default::default()
     };
 
-    let config = Config::build(Some(empty_file_config), cli_args).expect("Config build failed");
+    let config = Config::build(Some(empty_file_config), FileConfig::default(), cli_args)
+        .expect("Config build failed");
 
     assert!(config.debug_mode); // From CLI
     assert_eq!(
@@ -196,6 +208,8 @@ fn test_build_empty_file_config_uses_defaults_and_cli() {
 
 // --- Tests for load_config_file (requires filesystem interaction) ---
 
+use std::borrow::Cow;
+use std::collections::BTreeSet;
 use std::env;
 use std::fs::{self, File};
 use std::io::Write;
@@ -494,8 +508,9 @@ fn test_build_log_directory_determination_with_xdg_data_home_env_var() {
         debug: false,
         init: false,
         direct_selection: None,
+        ..Default::default()
     };
-    let config_result = Config::build(None, cli_args);
+    let config_result = Config::build(None, FileConfig::default(), cli_args);
 
     // Restore XDG_DATA_HOME immediately after building
     if let Some(val) = original_xdg_data_home {
@@ -580,6 +595,158 @@ fn test_cli_args_init_flag_parsing() {
     assert_eq!(cli_args.direct_selection, None);
 }
 
+#[test]
+fn test_cli_args_exact_requires_direct_selection() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--exact", "my_project"]);
+    assert!(cli_args.exact);
+    assert_eq!(cli_args.direct_selection.as_deref(), Some("my_project"));
+
+    assert!(CliArgs::try_parse_from(["tmux-sessionizer", "--exact"]).is_err());
+}
+
+#[test]
+fn test_cli_detach_sets_config() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--detach", "my_project"]);
+    let config = Config::build(None, FileConfig::default(), cli_args).unwrap();
+    assert!(config.detach);
+    assert_eq!(config.direct_selection.as_deref(), Some("my_project"));
+
+    let config = Config::build(None, FileConfig::default(), CliArgs::default()).unwrap();
+    assert!(!config.detach);
+}
+
+#[test]
+fn test_cli_print_format() {
+    let config = |args: &[&str]| {
+        let cli_args = CliArgs::try_parse_from(args).expect("Failed to parse arguments");
+        Config::build(None, FileConfig::default(), cli_args).unwrap()
+    };
+    assert_eq!(config(&["tmux-sessionizer"]).print, None);
+    assert_eq!(
+        config(&["tmux-sessionizer", "--print", "api"]).print,
+        Some(PrintFormat::Plain)
+    );
+    assert_eq!(
+        config(&["tmux-sessionizer", "--print=json"]).print,
+        Some(PrintFormat::Json)
+    );
+
+    assert!(CliArgs::try_parse_from(["tmux-sessionizer", "--print=yaml"]).is_err());
+    assert!(CliArgs::try_parse_from(["tmux-sessionizer", "--print", "--detach"]).is_err());
+}
+
+#[test]
+fn test_cli_filter_options_set_entry_filter() {
+    let cli_args = CliArgs::parse_from([
+        "tmux-sessionizer",
+        "--query",
+        "api",
+        "--type",
+        "worktree",
+        "--type",
+        "git",
+        "--repo",
+        "api",
+        "--running",
+    ]);
+    let config = Config::build(None, FileConfig::default(), cli_args).unwrap();
+
+    assert_eq!(config.finder_query.as_deref(), Some("api"));
+    assert_eq!(
+        config.entry_filter,
+        EntryFilter {
+            types: vec![ProjectType::Worktree, ProjectType::Git],
+            repo: Some("api".to_string()),
+            running: true,
+        }
+    );
+
+    assert!(CliArgs::try_parse_from(["tmux-sessionizer", "--query", "api", "my_project"]).is_err());
+}
+
+#[test]
+fn test_finder_view_from_file_and_cli() {
+    let file_config: FileConfig =
+        toml::from_str("[finder]\nview = \"tree\"").expect("Failed to parse finder table");
+    let config =
+        Config::build(Some(file_config), FileConfig::default(), CliArgs::default()).unwrap();
+    assert_eq!(config.finder.view, Some(FinderView::Tree));
+
+    let file_config: FileConfig = toml::from_str("[finder]\nview = \"tree\"").unwrap();
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--view", "flat"]);
+    let config = Config::build(Some(file_config), FileConfig::default(), cli_args).unwrap();
+    assert_eq!(config.finder.view, Some(FinderView::Flat));
+    assert_eq!(
+        config.sources.get("finder"),
+        Some(&ValueSource::CommandLine)
+    );
+
+    assert!(toml::from_str::<FileConfig>("[finder]\nview = \"grid\"").is_err());
+}
+
+#[test]
+fn test_finder_appearance_and_bindings_merge_per_key() {
+    let base: FinderConfig = toml::from_str(
+        r#"
+prompt = "> "
+height = "40%"
+layout = "reverse"
+color = "light"
+icons = "nerd"
+
+[bindings]
+ctrl-d = "kill-session"
+ctrl-y = "copy-path"
+"#,
+    )
+    .expect("Failed to parse finder settings");
+    let overrides: FinderConfig = toml::from_str(
+        r#"
+header = "Projects"
+border = true
+
+[bindings]
+ctrl-y = "open-detached"
+ctrl-w = "new-worktree"
+"#,
+    )
+    .unwrap();
+
+    let merged = base.merged_with(overrides);
+    assert_eq!(merged.prompt.as_deref(), Some("> "));
+    assert_eq!(merged.height.as_deref(), Some("40%"));
+    assert_eq!(merged.layout, Some(FinderLayout::Reverse));
+    assert_eq!(merged.color.as_deref(), Some("light"));
+    assert_eq!(merged.header.as_deref(), Some("Projects"));
+    assert_eq!(merged.border, Some(true));
+    assert_eq!(merged.icons, Some(FinderIcons::Nerd));
+    assert_eq!(
+        merged.bindings,
+        Some(BTreeMap::from([
+            ("ctrl-d".to_string(), FinderAction::KillSession),
+            ("ctrl-w".to_string(), FinderAction::NewWorktree),
+            ("ctrl-y".to_string(), FinderAction::OpenDetached),
+        ]))
+    );
+
+    let config = Config::build(
+        Some(FileConfig {
+            finder: Some(merged),
+            ..FileConfig::default()
+        }),
+        FileConfig::default(),
+        CliArgs::default(),
+    )
+    .unwrap();
+    let output = inspect::render(&config, &[], &BTreeMap::new());
+    assert!(output.contains("bindings = { ctrl-d = \"kill-session\""));
+    assert!(output.contains("border = true"));
+
+    assert!(toml::from_str::<FinderConfig>("[bindings]\nctrl-d = \"explode\"").is_err());
+    assert!(toml::from_str::<FinderConfig>("layout = \"sideways\"").is_err());
+    assert!(toml::from_str::<FinderConfig>("icons = \"emoji\"").is_err());
+}
+
 #[test]
 fn test_init_command_integration() {
     // This test verifies that when --init is provided, the application should
@@ -635,7 +802,7 @@ fn test_config_new_propagates_xdg_error_for_log_dir() {
     unsafe { std::env::remove_var("XDG_DATA_HOME") };
 
     let cli_args = CliArgs::parse_from(Vec::<String>::new()); // Simulate no CLI args for parse()
-    let build_result = Config::build(None, cli_args);
+    let build_result = Config::build(None, FileConfig::default(), cli_args);
 
     // Restore environment variables
     if let Some(val) = original_home {
@@ -665,3 +832,1183 @@ fn test_config_new_propagates_xdg_error_for_log_dir() {
     }
     // This test is more of a best-effort due to difficulties in reliably inducing BaseDirs failure.
 }
+
+#[test]
+fn test_session_match_defaults_to_name() {
+    let config = Config::build(
+        None,
+        FileConfig::default(),
+        CliArgs::parse_from(["tmux-sessionizer"]),
+    )
+    .expect("Config build failed");
+    assert_eq!(config.session_match, SessionMatch::Name);
+}
+
+#[test]
+fn test_session_match_from_file_config() {
+    let file_config: FileConfig =
+        toml::from_str(r#"session_match = "path""#).expect("Failed to parse session_match");
+    assert_eq!(file_config.session_match, Some(SessionMatch::Path));
+
+    let config = Config::build(
+        Some(file_config),
+        FileConfig::default(),
+        CliArgs::parse_from(["tmux-sessionizer"]),
+    )
+    .expect("Config build failed");
+    assert_eq!(config.session_match, SessionMatch::Path);
+}
+
+#[test]
+fn test_session_match_rejects_unknown_value() {
+    let result = toml::from_str::<FileConfig>(r#"session_match = "window""#);
+    assert!(result.is_err());
+}
+
+#[test]
+fn test_cli_args_last_subcommand_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "last"]);
+    assert_eq!(cli_args.command, Some(Command::Last));
+    assert_eq!(cli_args.direct_selection, None);
+
+    let config = Config::build(None, FileConfig::default(), cli_args).expect("Config build failed");
+    assert_eq!(config.command, Some(Command::Last));
+
+    // Anything that is not a subcommand is still a direct selection.
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "my_project"]);
+    assert_eq!(cli_args.command, None);
+    assert_eq!(cli_args.direct_selection, Some("my_project".to_string()));
+}
+
+#[test]
+fn test_cli_args_worktree_subcommand_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "worktree"]);
+    assert_eq!(cli_args.command, Some(Command::Worktree));
+    assert_eq!(cli_args.direct_selection, None);
+}
+
+#[test]
+fn test_cli_args_subcommand_after_direct_selection_is_rejected() {
+    assert!(CliArgs::try_parse_from(["tmux-sessionizer", "foo", "last"]).is_err());
+}
+
+#[test]
+fn test_cli_args_select_project_named_like_subcommand() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--", "man"]);
+    assert_eq!(cli_args.command, None);
+    assert_eq!(cli_args.direct_selection, Some("man".to_string()));
+
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--print", "last"]);
+    assert_eq!(cli_args.command, None);
+    assert_eq!(cli_args.direct_selection, Some("last".to_string()));
+}
+
+#[test]
+fn test_cli_args_options_after_subcommand() {
+    let cli_args =
+        CliArgs::parse_from(["tmux-sessionizer", "last", "--profile", "work", "--detach"]);
+    assert_eq!(cli_args.command, Some(Command::Last));
+    assert_eq!(cli_args.profile, Some("work".to_string()));
+    assert!(cli_args.detach);
+}
+
+#[test]
+fn test_env_and_direnv_from_file_config() {
+    let file_config: FileConfig = toml::from_str(
+        r#"
+direnv = true
+
+[env]
+EDITOR = "nvim"
+RUST_LOG = "info"
+"#,
+    )
+    .expect("Failed to parse env table");
+
+    let config = Config::build(
+        Some(file_config),
+        FileConfig::default(),
+        CliArgs::parse_from(["tmux-sessionizer"]),
+    )
+    .expect("Config build failed");
+
+    assert!(config.direnv);
+    assert_eq!(config.env.len(), 2);
+    assert_eq!(config.env.get("EDITOR"), Some(&"nvim".to_string()));
+    assert_eq!(config.env.get("RUST_LOG"), Some(&"info".to_string()));
+}
+
+#[test]
+fn test_load_project_config() {
+    let temp_dir = tempdir().unwrap();
+    assert!(load_project_config(temp_dir.path()).unwrap().is_none());
+
+    fs::write(
+        temp_dir.path().join(PROJECT_CONFIG_FILE_NAME),
+        "[env]\nDATABASE_URL = \"postgres://localhost/app\"\n",
+    )
+    .unwrap();
+    let project_config = load_project_config(temp_dir.path())
+        .unwrap()
+        .expect("Expected project config to be loaded");
+    assert_eq!(
+        project_config.env.unwrap().get("DATABASE_URL"),
+        Some(&"postgres://localhost/app".to_string())
+    );
+
+    fs::write(
+        temp_dir.path().join(PROJECT_CONFIG_FILE_NAME),
+        "unknown = true\n",
+    )
+    .unwrap();
+    assert!(matches!(
+        load_project_config(temp_dir.path()),
+        Err(ConfigError::FileParseError { .. })
+    ));
+}
+
+#[test]
+fn test_hooks_from_file_config() {
+    let file_config: FileConfig = toml::from_str(
+        r#"
+[hooks]
+pre_create = "git -C {path} fetch --quiet"
+on_create = "nvim ."
+"#,
+    )
+    .expect("Failed to parse hooks table");
+
+    let config = Config::build(
+        Some(file_config),
+        FileConfig::default(),
+        CliArgs::parse_from(["tmux-sessionizer"]),
+    )
+    .expect("Config build failed");
+
+    assert_eq!(
+        config.hooks.pre_create.as_deref(),
+        Some("git -C {path} fetch --quiet")
+    );
+    assert_eq!(config.hooks.on_create.as_deref(), Some("nvim ."));
+    assert_eq!(config.hooks.on_switch, None);
+}
+
+#[test]
+fn test_hooks_rejects_unknown_hook() {
+    let result: Result<FileConfig, _> = toml::from_str("[hooks]\non_attach = \"ls\"\n");
+    assert!(result.is_err(), "Unknown hook names should be rejected");
+}
+
+#[test]
+fn test_import_layouts_defaults_to_enabled() {
+    let config = Config::build(
+        None,
+        FileConfig::default(),
+        CliArgs::parse_from(["tmux-sessionizer"]),
+    )
+    .expect("Config build failed");
+    assert!(config.import_layouts);
+
+    let file_config: FileConfig =
+        toml::from_str("import_layouts = false").expect("Failed to parse import_layouts");
+    let config = Config::build(
+        Some(file_config),
+        FileConfig::default(),
+        CliArgs::parse_from(["tmux-sessionizer"]),
+    )
+    .expect("Config build failed");
+    assert!(!config.import_layouts);
+}
+
+#[test]
+fn test_cli_args_save_and_restore_subcommands_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "save"]);
+    assert_eq!(cli_args.command, Some(Command::Save));
+
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "restore"]);
+    assert_eq!(cli_args.command, Some(Command::Restore { session: None }));
+
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "restore", "my-project"]);
+    assert_eq!(
+        cli_args.command,
+        Some(Command::Restore {
+            session: Some("my-project".to_string())
+        })
+    );
+}
+
+const PROFILES_CONFIG: &str = r#"
+search_paths = ["/tmp"]
+exclude_patterns = ["/target/"]
+
+[env]
+EDITOR = "nvim"
+
+[hooks]
+on_create = "ls"
+
+[profiles.work]
+search_paths = ["/var/tmp"]
+default_new_project_path = "/var/tmp/new"
+
+[profiles.work.env]
+AWS_PROFILE = "work"
+
+[profiles.work.hooks]
+pre_create = "git fetch"
+
+[profiles.oss]
+exclude_patterns = ["/vendor/"]
+"#;
+
+#[test]
+fn test_profile_overrides_global_settings() {
+    let file_config: FileConfig =
+        toml::from_str(PROFILES_CONFIG).expect("Failed to parse profiles");
+    let cli_args = CliArgs {
+        profile: Some("work".to_string()),
+        ..Default::default()
+    };
+
+    let config = Config::build(Some(file_config), FileConfig::default(), cli_args)
+        .expect("Config build failed");
+
+    assert_eq!(config.profile.as_deref(), Some("work"));
+    assert_eq!(
+        config.profile_names,
+        vec!["oss".to_string(), "work".to_string()]
+    );
+    assert_eq!(config.search_paths, vec![PathBuf::from("/var/tmp")]);
+    assert_eq!(
+        config.default_new_project_path,
+        PathBuf::from("/var/tmp/new")
+    );
+    // Settings the profile does not set are kept from the global configuration.
+    assert_eq!(config.exclude_patterns.len(), 1);
+    assert_eq!(config.exclude_patterns[0].as_str(), "/target/");
+    assert_eq!(config.env.get("EDITOR"), Some(&"nvim".to_string()));
+    assert_eq!(config.env.get("AWS_PROFILE"), Some(&"work".to_string()));
+    assert_eq!(config.hooks.on_create.as_deref(), Some("ls"));
+    assert_eq!(config.hooks.pre_create.as_deref(), Some("git fetch"));
+}
+
+#[test]
+fn test_environment_overrides_profile() {
+    let file_config: FileConfig =
+        toml::from_str(PROFILES_CONFIG).expect("Failed to parse profiles");
+    let env_config = FileConfig {
+        search_paths: Some(vec!["/env".to_string()]),
+        ..Default::default()
+    };
+    let cli_args = CliArgs {
+        profile: Some("work".to_string()),
+        ..Default::default()
+    };
+
+    let config =
+        Config::build(Some(file_config), env_config, cli_args).expect("Config build failed");
+
+    assert_eq!(config.search_paths, vec![PathBuf::from("/env")]);
+    assert_eq!(
+        config.sources.get("search_paths"),
+        Some(&ValueSource::Environment)
+    );
+    // Settings only the profile sets still apply.
+    assert_eq!(
+        config.default_new_project_path,
+        PathBuf::from("/var/tmp/new")
+    );
+    assert_eq!(
+        config.sources.get("default_new_project_path"),
+        Some(&ValueSource::Profile("work".to_string()))
+    );
+}
+
+#[test]
+fn test_without_profile_uses_global_settings() {
+    let file_config: FileConfig =
+        toml::from_str(PROFILES_CONFIG).expect("Failed to parse profiles");
+    let config = Config::build(Some(file_config), FileConfig::default(), CliArgs::default())
+        .expect("Config build failed");
+
+    assert_eq!(config.profile, None);
+    assert_eq!(config.search_paths, vec![PathBuf::from("/tmp")]);
+    assert!(!config.env.contains_key("AWS_PROFILE"));
+}
+
+#[test]
+fn test_unknown_profile_is_an_error() {
+    let file_config: FileConfig =
+        toml::from_str(PROFILES_CONFIG).expect("Failed to parse profiles");
+    let cli_args = CliArgs {
+        profile: Some("home".to_string()),
+        ..Default::default()
+    };
+
+    let result = Config::build(Some(file_config), FileConfig::default(), cli_args);
+
+    match result {
+        Err(ConfigError::UnknownProfile { name, available }) => {
+            assert_eq!(name, "home");
+            assert_eq!(available, "oss, work");
+        }
+        other => panic!("Expected UnknownProfile error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_cli_args_profile_flag_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--profile", "oss"]);
+    assert_eq!(cli_args.profile.as_deref(), Some("oss"));
+}
+
+#[test]
+fn test_next_profile_cycles_through_profiles_and_back_to_none() {
+    let mut config = Config {
+        profile_names: vec!["oss".to_string(), "work".to_string()],
+        ..Default::default()
+    };
+
+    assert_eq!(config.next_profile().as_deref(), Some("oss"));
+    config.profile = Some("oss".to_string());
+    assert_eq!(config.next_profile().as_deref(), Some("work"));
+    config.profile = Some("work".to_string());
+    assert_eq!(config.next_profile(), None);
+
+    config.profile_names.clear();
+    config.profile = None;
+    assert_eq!(config.next_profile(), None);
+}
+
+#[test]
+fn test_file_config_merge_overrides_and_merges_tables() {
+    let base: FileConfig = toml::from_str(
+        r#"
+search_paths = ["/base"]
+exclude_patterns = ["/target/"]
+[env]
+EDITOR = "vim"
+PAGER = "less"
+[hooks]
+on_create = "ls"
+"#,
+    )
+    .unwrap();
+    let overrides: FileConfig = toml::from_str(
+        r#"
+search_paths = ["/local"]
+[env]
+EDITOR = "nvim"
+[hooks]
+pre_create = "git fetch"
+"#,
+    )
+    .unwrap();
+
+    let merged = base.merge(overrides);
+
+    assert_eq!(merged.search_paths, Some(vec!["/local".to_string()]));
+    assert_eq!(merged.exclude_patterns, Some(vec!["/target/".to_string()]));
+    let env = merged.env.unwrap();
+    assert_eq!(env.get("EDITOR"), Some(&"nvim".to_string()));
+    assert_eq!(env.get("PAGER"), Some(&"less".to_string()));
+    let hooks = merged.hooks.unwrap();
+    assert_eq!(hooks.on_create.as_deref(), Some("ls"));
+    assert_eq!(hooks.pre_create.as_deref(), Some("git fetch"));
+}
+
+/// Loads and merges the configuration files of `config_dir` like `Config::load` does.
+fn load_merged_config_dir(
+    config_dir: &Path,
+) -> std::result::Result<Option<FileConfig>, ConfigError> {
+    let (layers, errors) = collect_config_dir_layers(config_dir);
+    match errors.into_iter().next() {
+        Some(error) => Err(error),
+        None => Ok(merge_layers(layers)),
+    }
+}
+
+#[test]
+fn test_load_config_from_dir_merges_includes_main_and_drop_ins() {
+    let temp_dir = tempdir().unwrap();
+    let config_dir = temp_dir.path();
+    let shared_dir = config_dir.join("shared");
+    fs::create_dir_all(&shared_dir).unwrap();
+    fs::create_dir_all(config_dir.join("conf.d")).unwrap();
+
+    fs::write(
+        shared_dir.join("base.toml"),
+        "search_paths = [\"/shared\"]\nadditional_paths = [\"/shared/extra\"]\ndefault_new_project_path = \"/shared/new\"\n",
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("tmux-sessionizer.toml"),
+        "include = [\"shared/base.toml\"]\nsearch_paths = [\"/main\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("conf.d").join("20-local.toml"),
+        "default_new_project_path = \"/local/new\"\n",
+    )
+    .unwrap();
+    fs::write(
+        config_dir.join("conf.d").join("10-team.toml"),
+        "default_new_project_path = \"/team/new\"\ndirenv = true\n",
+    )
+    .unwrap();
+    fs::write(config_dir.join("conf.d").join("README.md"), "not toml").unwrap();
+
+    let file_config = load_merged_config_dir(config_dir)
+        .unwrap()
+        .expect("Expected merged configuration");
+
+    assert_eq!(file_config.search_paths, Some(vec!["/main".to_string()]));
+    assert_eq!(
+        file_config.additional_paths,
+        Some(vec!["/shared/extra".to_string()])
+    );
+    assert_eq!(
+        file_config.default_new_project_path,
+        Some("/local/new".to_string())
+    );
+    assert_eq!(file_config.direnv, Some(true));
+}
+
+#[test]
+fn test_load_config_from_dir_with_only_drop_ins() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir_all(temp_dir.path().join("conf.d")).unwrap();
+    fs::write(
+        temp_dir.path().join("conf.d").join("local.toml"),
+        "direnv = true\n",
+    )
+    .unwrap();
+
+    let file_config = load_merged_config_dir(temp_dir.path()).unwrap().unwrap();
+    assert_eq!(file_config.direnv, Some(true));
+
+    let empty_dir = tempdir().unwrap();
+    assert!(load_merged_config_dir(empty_dir.path()).unwrap().is_none());
+}
+
+#[test]
+fn test_load_config_from_dir_detects_include_cycle() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(
+        temp_dir.path().join("tmux-sessionizer.toml"),
+        "include = [\"other.toml\"]\n",
+    )
+    .unwrap();
+    fs::write(
+        temp_dir.path().join("other.toml"),
+        "include = [\"tmux-sessionizer.toml\"]\n",
+    )
+    .unwrap();
+
+    let result = load_merged_config_dir(temp_dir.path());
+    assert!(matches!(result, Err(ConfigError::IncludeCycle { .. })));
+}
+
+#[test]
+fn test_load_config_from_dir_missing_include_is_an_error() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(
+        temp_dir.path().join("tmux-sessionizer.toml"),
+        "include = [\"missing.toml\"]\n",
+    )
+    .unwrap();
+
+    let result = load_merged_config_dir(temp_dir.path());
+    assert!(matches!(result, Err(ConfigError::FileReadError { .. })));
+}
+
+fn env_lookup(vars: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: BTreeMap<String, String> = vars
+        .iter()
+        .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+        .collect();
+    move |name| vars.get(name).cloned()
+}
+
+#[test]
+fn test_env_file_config_parses_variables() {
+    let env_config = env_file_config(env_lookup(&[
+        ("TMUX_SESSIONIZER_SEARCH_PATHS", "/a:/b::"),
+        ("TMUX_SESSIONIZER_ADDITIONAL_PATHS", "/c"),
+        (
+            "TMUX_SESSIONIZER_EXCLUDE_PATTERNS",
+            "/target/,/node_modules/",
+        ),
+        ("TMUX_SESSIONIZER_DEFAULT_NEW_PROJECT_PATH", "/new"),
+        ("TMUX_SESSIONIZER_SESSION_MATCH", "Path"),
+        ("TMUX_SESSIONIZER_DIRENV", "yes"),
+        ("TMUX_SESSIONIZER_IMPORT_LAYOUTS", "0"),
+        ("TMUX_SESSIONIZER_FINDER_BORDER", "true"),
+        ("TMUX_SESSIONIZER_UNRELATED", "ignored"),
+    ]))
+    .expect("Failed to parse environment");
+
+    assert_eq!(
+        env_config.search_paths,
+        Some(vec!["/a".to_string(), "/b".to_string()])
+    );
+    assert_eq!(env_config.additional_paths, Some(vec!["/c".to_string()]));
+    assert_eq!(
+        env_config.exclude_patterns,
+        Some(vec!["/target/".to_string(), "/node_modules/".to_string()])
+    );
+    assert_eq!(
+        env_config.default_new_project_path,
+        Some("/new".to_string())
+    );
+    assert_eq!(env_config.session_match, Some(SessionMatch::Path));
+    assert_eq!(env_config.direnv, Some(true));
+    assert_eq!(env_config.import_layouts, Some(false));
+    assert_eq!(
+        env_config.finder,
+        Some(FinderConfig {
+            border: Some(true),
+            ..FinderConfig::default()
+        })
+    );
+}
+
+#[test]
+fn test_env_file_config_ignores_empty_and_rejects_invalid_values() {
+    let env_config = env_file_config(env_lookup(&[("TMUX_SESSIONIZER_SEARCH_PATHS", "")]))
+        .expect("Failed to parse environment");
+    assert_eq!(env_config.search_paths, None);
+
+    let result = env_file_config(env_lookup(&[("TMUX_SESSIONIZER_DIRENV", "maybe")]));
+    match result {
+        Err(ConfigError::InvalidEnvVar { name, value, .. }) => {
+            assert_eq!(name, "TMUX_SESSIONIZER_DIRENV");
+            assert_eq!(value, "maybe");
+        }
+        other => panic!("Expected InvalidEnvVar error, got {other:?}"),
+    }
+
+    let result = env_file_config(env_lookup(&[("TMUX_SESSIONIZER_SESSION_MATCH", "both")]));
+    assert!(matches!(result, Err(ConfigError::InvalidEnvVar { .. })));
+}
+
+#[test]
+fn test_precedence_defaults_file_env_cli() {
+    let file_config: FileConfig = toml::from_str(
+        r#"
+search_paths = ["/file/search"]
+additional_paths = ["/file/extra"]
+exclude_patterns = ["/file/"]
+default_new_project_path = "/file/new"
+"#,
+    )
+    .unwrap();
+    let env_config = env_file_config(env_lookup(&[
+        ("TMUX_SESSIONIZER_SEARCH_PATHS", "/env/search"),
+        ("TMUX_SESSIONIZER_ADDITIONAL_PATHS", "/env/extra"),
+        ("TMUX_SESSIONIZER_DIRENV", "true"),
+    ]))
+    .unwrap();
+    let cli_args = CliArgs::parse_from([
+        "tmux-sessionizer",
+        "--search-path",
+        "/cli/one",
+        "--search-path",
+        "/cli/two",
+        "--exclude",
+        "/cli/",
+        "--new-project-path",
+        "/cli/new",
+    ]);
+
+    let config =
+        Config::build(Some(file_config), env_config, cli_args).expect("Config build failed");
+
+    // CLI wins over environment and file
+    assert_eq!(
+        config.search_paths,
+        vec![PathBuf::from("/cli/one"), PathBuf::from("/cli/two")]
+    );
+    assert_eq!(config.exclude_patterns[0].as_str(), "/cli/");
+    assert_eq!(config.default_new_project_path, PathBuf::from("/cli/new"));
+    // Environment wins over file
+    assert_eq!(config.additional_paths, vec![PathBuf::from("/env/extra")]);
+    assert!(config.direnv);
+    // Defaults remain for keys set nowhere
+    assert_eq!(config.session_match, SessionMatch::Name);
+}
+
+#[test]
+fn test_cli_args_config_and_path_options_parsing() {
+    let cli_args = CliArgs::parse_from([
+        "tmux-sessionizer",
+        "--config",
+        "/tmp/custom.toml",
+        "--additional-path",
+        "/x",
+        "--additional-path",
+        "/y",
+    ]);
+    assert_eq!(cli_args.config, Some(PathBuf::from("/tmp/custom.toml")));
+    assert_eq!(
+        cli_args.additional_paths,
+        vec!["/x".to_string(), "/y".to_string()]
+    );
+    assert!(cli_args.search_paths.is_empty());
+    assert_eq!(cli_args.new_project_path, None);
+}
+
+#[test]
+fn test_cli_args_config_subcommand_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "config", "show"]);
+    assert_eq!(
+        cli_args.command,
+        Some(Command::Config {
+            action: ConfigCommand::Show
+        })
+    );
+
+    let cli_args =
+        CliArgs::parse_from(["tmux-sessionizer", "config", "validate", "/tmp/other.toml"]);
+    assert_eq!(
+        cli_args.command,
+        Some(Command::Config {
+            action: ConfigCommand::Validate {
+                file: Some(PathBuf::from("/tmp/other.toml"))
+            }
+        })
+    );
+}
+
+#[test]
+fn test_config_schema_and_migrate_subcommand_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "config", "schema"]);
+    assert_eq!(
+        cli_args.command,
+        Some(Command::Config {
+            action: ConfigCommand::Schema
+        })
+    );
+
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "config", "migrate"]);
+    assert_eq!(
+        cli_args.command,
+        Some(Command::Config {
+            action: ConfigCommand::Migrate { file: None }
+        })
+    );
+}
+
+#[test]
+fn test_completions_and_man_subcommand_parsing() {
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "completions", "nushell"]);
+    assert_eq!(
+        cli_args.command,
+        Some(Command::Completions {
+            shell: CompletionShell::Nushell
+        })
+    );
+
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "man"]);
+    assert_eq!(cli_args.command, Some(Command::Man));
+
+    assert!(CliArgs::try_parse_from(["tmux-sessionizer", "completions", "tcsh"]).is_err());
+}
+
+#[test]
+fn test_build_records_value_sources() {
+    let file_config: FileConfig = toml::from_str(PROFILES_CONFIG).unwrap();
+    let env_config = env_file_config(env_lookup(&[("TMUX_SESSIONIZER_DIRENV", "1")])).unwrap();
+    let cli_args = CliArgs::parse_from([
+        "tmux-sessionizer",
+        "--profile",
+        "work",
+        "--exclude",
+        "/cli/",
+    ]);
+
+    let config =
+        Config::build(Some(file_config), env_config, cli_args).expect("Config build failed");
+
+    assert_eq!(
+        config.sources.get("search_paths"),
+        Some(&ValueSource::Profile("work".to_string()))
+    );
+    assert_eq!(
+        config.sources.get("exclude_patterns"),
+        Some(&ValueSource::CommandLine)
+    );
+    assert_eq!(
+        config.sources.get("direnv"),
+        Some(&ValueSource::Environment)
+    );
+    assert_eq!(
+        config.sources.get("env"),
+        Some(&ValueSource::Profile("work".to_string()))
+    );
+    assert_eq!(config.sources.get("session_match"), None);
+}
+
+#[test]
+fn test_render_shows_effective_values_and_sources() {
+    let file_config: FileConfig = toml::from_str(PROFILES_CONFIG).unwrap();
+    let env_config = env_file_config(env_lookup(&[("TMUX_SESSIONIZER_DIRENV", "1")])).unwrap();
+    let config =
+        Config::build(Some(file_config), env_config, CliArgs::default()).expect("build failed");
+    let main_file = PathBuf::from("/cfg/tmux-sessionizer.toml");
+    let file_sources = BTreeMap::from([
+        ("search_paths", main_file.clone()),
+        ("exclude_patterns", main_file.clone()),
+    ]);
+
+    let output = inspect::render(&config, &[main_file], &file_sources);
+
+    assert!(output.contains("#   /cfg/tmux-sessionizer.toml\n"));
+    assert!(output.contains("# Profile: none\n"));
+    assert!(output.contains("search_paths = [\"/tmp\"]  # /cfg/tmux-sessionizer.toml\n"));
+    assert!(output.contains("direnv = true  # TMUX_SESSIONIZER_DIRENV\n"));
+    assert!(output.contains("env = { EDITOR = \"nvim\" }  # configuration file\n"));
+    assert!(output.contains("hooks = { on_create = \"ls\" }  # configuration file\n"));
+    assert!(output.contains("session_match = \"name\"  # default\n"));
+}
+
+#[test]
+fn test_collect_env_file_config_reports_every_invalid_variable() {
+    let (file_config, errors) = collect_env_file_config(env_lookup(&[
+        ("TMUX_SESSIONIZER_SESSION_MATCH", "both"),
+        ("TMUX_SESSIONIZER_DIRENV", "maybe"),
+        ("TMUX_SESSIONIZER_IMPORT_LAYOUTS", "off"),
+    ]));
+
+    assert_eq!(errors.len(), 2);
+    assert!(
+        errors
+            .iter()
+            .all(|e| matches!(e, ConfigError::InvalidEnvVar { .. }))
+    );
+    assert_eq!(file_config.import_layouts, Some(false));
+}
+
+#[test]
+fn test_validate_reports_every_problem() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("tmux-sessionizer.toml");
+    fs::write(
+        &config_file,
+        format!(
+            r#"
+include = ["missing.toml"]
+search_paths = ["{dir}", "{dir}/missing-search"]
+exclude_patterns = ["[unclosed", "/target/"]
+
+[profiles.work]
+additional_paths = ["{dir}/missing-work"]
+exclude_patterns = ["(unclosed"]
+"#,
+            dir = temp_dir.path().display()
+        ),
+    )
+    .unwrap();
+    let cli_args = CliArgs {
+        profile: Some("home".to_string()),
+        ..Default::default()
+    };
+
+    let problems = inspect::validate(&cli_args, Some(&config_file)).unwrap();
+
+    assert_eq!(problems.len(), 6, "{problems:?}");
+    assert!(matches!(problems[0], ConfigError::FileReadError { .. }));
+    let count = |f: fn(&ConfigError) -> bool| problems.iter().filter(|e| f(e)).count();
+    assert_eq!(count(|e| matches!(e, ConfigError::InvalidRegex { .. })), 2);
+    assert_eq!(count(|e| matches!(e, ConfigError::InvalidPath(_))), 2);
+    assert_eq!(
+        count(
+            |e| matches!(e, ConfigError::UnknownProfile { available, .. } if available == "work")
+        ),
+        1
+    );
+}
+
+#[test]
+fn test_validate_accepts_valid_file() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("valid.toml");
+    fs::write(
+        &config_file,
+        format!("search_paths = [\"{}\"]\n", temp_dir.path().display()),
+    )
+    .unwrap();
+
+    let problems = inspect::validate(&CliArgs::default(), Some(&config_file)).unwrap();
+    assert!(problems.is_empty(), "{problems:?}");
+}
+
+#[test]
+fn test_build_compiles_path_and_exclude_globs() {
+    let file_config: FileConfig = toml::from_str(
+        r#"
+search_paths = ["/tmp", "/tmp/*/"]
+additional_paths = ["/srv/**/services"]
+exclude_globs = ["**/*-archive", "~/legacy/*"]
+"#,
+    )
+    .unwrap();
+
+    let config = Config::build(Some(file_config), FileConfig::default(), CliArgs::default())
+        .expect("Config build failed");
+
+    assert_eq!(config.search_paths, vec![PathBuf::from("/tmp")]);
+    assert_eq!(config.search_globs.len(), 1);
+    assert!(config.additional_paths.is_empty());
+    assert_eq!(config.additional_globs[0].as_str(), "/srv/**/services");
+    assert_eq!(config.exclude_globs.len(), 2);
+    assert!(config.exclude_globs[0].matches("/home/me/dev/shop-archive"));
+    let home = get_home_dir_for_test();
+    assert!(config.exclude_globs[1].matches_path(&home.join("legacy").join("old-project")));
+}
+
+#[test]
+fn test_build_rejects_invalid_globs() {
+    let invalid_path: FileConfig = toml::from_str(r#"search_paths = ["/tmp/[abc"]"#).unwrap();
+    let result = Config::build(
+        Some(invalid_path),
+        FileConfig::default(),
+        CliArgs::default(),
+    );
+    assert!(matches!(result, Err(ConfigError::InvalidGlob { .. })));
+
+    let cli_args = CliArgs::parse_from(["tmux-sessionizer", "--exclude-glob", "a/***"]);
+    let result = Config::build(None, FileConfig::default(), cli_args);
+    assert!(matches!(result, Err(ConfigError::InvalidGlob { pattern, .. }) if pattern == "a/***"));
+}
+
+#[test]
+fn test_build_expands_environment_variables_in_paths() {
+    // A variable name no other test uses, so setting it cannot race with them
+    unsafe { env::set_var("TSZ_TEST_EXPAND_WORKSPACE", "/srv/workspace") };
+    let file_config: FileConfig = toml::from_str(
+        r#"
+search_paths = ["$TSZ_TEST_EXPAND_WORKSPACE/src", "${TSZ_TEST_EXPAND_UNSET:-/opt}/dev"]
+default_new_project_path = "${TSZ_TEST_EXPAND_WORKSPACE}/new"
+exclude_globs = ["$TSZ_TEST_EXPAND_WORKSPACE/archive/*"]
+"#,
+    )
+    .unwrap();
+
+    let config = Config::build(Some(file_config), FileConfig::default(), CliArgs::default())
+        .expect("Config build failed");
+    unsafe { env::remove_var("TSZ_TEST_EXPAND_WORKSPACE") };
+
+    assert_eq!(
+        config.search_paths,
+        vec![
+            PathBuf::from("/srv/workspace/src"),
+            PathBuf::from("/opt/dev")
+        ]
+    );
+    assert_eq!(
+        config.default_new_project_path,
+        PathBuf::from("/srv/workspace/new")
+    );
+    assert!(config.exclude_globs[0].matches("/srv/workspace/archive/old"));
+}
+
+#[test]
+fn test_build_rejects_undefined_variable_in_path() {
+    use crate::error::PathExpansionError;
+
+    let file_config: FileConfig =
+        toml::from_str(r#"additional_paths = ["$TSZ_TEST_EXPAND_NEVER_SET/src"]"#).unwrap();
+
+    let result = Config::build(Some(file_config), FileConfig::default(), CliArgs::default());
+
+    match result {
+        Err(ConfigError::PathExpansion {
+            path,
+            source: PathExpansionError::UndefinedVariable { name },
+        }) => {
+            assert_eq!(path, "$TSZ_TEST_EXPAND_NEVER_SET/src");
+            assert_eq!(name, "TSZ_TEST_EXPAND_NEVER_SET");
+        }
+        other => panic!("Expected an undefined variable error, got {other:?}"),
+    }
+}
+
+#[test]
+fn test_schema_matches_committed_file() {
+    assert_eq!(
+        inspect::schema(),
+        include_str!("../../examples/tmux-sessionizer.schema.json"),
+        "Regenerate the schema with `tmux-sessionizer config schema > examples/tmux-sessionizer.schema.json`"
+    );
+}
+
+/// Returns the fields listed in the "unknown field" error for `bogus_field` in `prefix`.
+fn accepted_fields(prefix: &str) -> BTreeSet<String> {
+    let error = toml::from_str::<FileConfig>(&format!("{prefix}bogus_field = 0"))
+        .expect_err("Unknown fields must be rejected")
+        .to_string();
+    let (_, expected) = error
+        .split_once("expected")
+        .expect("Error must list the expected fields");
+    expected
+        .split('`')
+        .skip(1)
+        .step_by(2)
+        .map(str::to_string)
+        .collect()
+}
+
+#[test]
+fn test_schema_properties_match_deserialized_fields() {
+    let schema: serde_json::Value = serde_json::from_str(&inspect::schema()).unwrap();
+    let properties = |object: &serde_json::Value| -> BTreeSet<String> {
+        assert_eq!(
+            object["additionalProperties"],
+            serde_json::Value::Bool(false)
+        );
+        object["properties"]
+            .as_object()
+            .expect("Schema object must have properties")
+            .keys()
+            .cloned()
+            .collect()
+    };
+
+    assert_eq!(properties(&schema), accepted_fields(""));
+    assert_eq!(
+        properties(&schema["definitions"]["ProfileConfig"]),
+        accepted_fields("[profiles.work]\n")
+    );
+    assert_eq!(
+        properties(&schema["definitions"]["HooksConfig"]),
+        accepted_fields("[hooks]\n")
+    );
+    assert_eq!(
+        properties(&schema["definitions"]["FinderConfig"]),
+        accepted_fields("[finder]\n")
+    );
+    for key in CONFIG_KEYS {
+        assert!(
+            properties(&schema).contains(*key),
+            "{key} missing in schema"
+        );
+    }
+}
+
+/// A format whose version 2 renamed `new_projects_dir` to `default_new_project_path`, to test
+/// the migrations before the current format renames any key.
+const TEST_FORMAT: migration::Format = migration::Format {
+    version: 2,
+    renamed_keys: &[(2, "new_projects_dir", "default_new_project_path")],
+    renamed_env_vars: &[("NEW_PROJECTS_DIR", "DEFAULT_NEW_PROJECT_PATH")],
+};
+
+const VERSION_1_CONFIG: &str = r#"# Projects
+search_paths = ["~/dev"]
+# Where new projects go
+new_projects_dir = "~/new" # trailing comment
+direnv = true
+
+[profiles.work]
+new_projects_dir = "~/work"
+
+[profiles.oss]
+default_new_project_path = "~/oss"
+new_projects_dir = "~/ignored"
+"#;
+
+#[test]
+fn test_migrate_content_renames_deprecated_keys() {
+    let (content, deprecations) =
+        migration::migrate_content(&TEST_FORMAT, Path::new("old.toml"), VERSION_1_CONFIG).unwrap();
+
+    let file_config: FileConfig = toml::from_str(&content).expect("Migrated content must load");
+    assert_eq!(
+        file_config.default_new_project_path.as_deref(),
+        Some("~/new")
+    );
+    let profiles = file_config.profiles.unwrap();
+    assert_eq!(
+        profiles["work"].default_new_project_path.as_deref(),
+        Some("~/work")
+    );
+    assert_eq!(
+        profiles["oss"].default_new_project_path.as_deref(),
+        Some("~/oss")
+    );
+    assert_eq!(
+        deprecations
+            .iter()
+            .map(|d| (d.key.as_str(), d.replacement.as_str(), d.ignored))
+            .collect::<Vec<_>>(),
+        vec![
+            ("new_projects_dir", "default_new_project_path", false),
+            (
+                "profiles.work.new_projects_dir",
+                "profiles.work.default_new_project_path",
+                false
+            ),
+            (
+                "profiles.oss.new_projects_dir",
+                "profiles.oss.default_new_project_path",
+                true
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_migrate_content_checks_version() {
+    let current = "version = 2\nnew_projects_dir = \"~/new\"\n";
+    let (content, deprecations) =
+        migration::migrate_content(&TEST_FORMAT, Path::new("current.toml"), current).unwrap();
+    assert!(matches!(content, Cow::Borrowed(_)));
+    assert!(deprecations.is_empty());
+
+    let result =
+        migration::migrate_content(&TEST_FORMAT, Path::new("future.toml"), "version = 3\n");
+    assert!(
+        matches!(
+            result,
+            Err(ConfigError::UnsupportedVersion {
+                version: 3,
+                supported: 2,
+                ..
+            })
+        ),
+        "{result:?}"
+    );
+}
+
+#[test]
+fn test_migrate_file_rewrites_file_and_keeps_backup() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("tmux-sessionizer.toml");
+    fs::write(&config_file, VERSION_1_CONFIG).unwrap();
+
+    let migrated = migration::migrate_file(&TEST_FORMAT, &config_file)
+        .unwrap()
+        .expect("Version 1 file must be migrated");
+
+    assert_eq!(migrated.from_version, 1);
+    assert_eq!(migrated.deprecations.len(), 3);
+    assert_eq!(
+        migrated.backup,
+        temp_dir.path().join("tmux-sessionizer.toml.bak")
+    );
+    assert_eq!(
+        fs::read_to_string(&migrated.backup).unwrap(),
+        VERSION_1_CONFIG
+    );
+    let content = fs::read_to_string(&config_file).unwrap();
+    assert!(
+        content.starts_with("version = 2\n# Projects\n"),
+        "{content}"
+    );
+    assert!(
+        content.contains(
+            "# Where new projects go\ndefault_new_project_path = \"~/new\" # trailing comment\n"
+        ),
+        "{content}"
+    );
+    assert!(!content.contains("new_projects_dir"), "{content}");
+
+    assert!(
+        migration::migrate_file(&TEST_FORMAT, &config_file)
+            .unwrap()
+            .is_none()
+    );
+}
+
+#[test]
+fn test_migrate_file_leaves_current_file_alone() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("tmux-sessionizer.toml");
+    let content = "version = 2\n# Projects\nsearch_paths = [\"~/dev\"]\n";
+    fs::write(&config_file, content).unwrap();
+
+    assert!(
+        migration::migrate_file(&TEST_FORMAT, &config_file)
+            .unwrap()
+            .is_none()
+    );
+    assert_eq!(fs::read_to_string(&config_file).unwrap(), content);
+    assert!(!temp_dir.path().join("tmux-sessionizer.toml.bak").exists());
+}
+
+#[test]
+fn test_migrate_file_keeps_existing_backup() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("tmux-sessionizer.toml");
+    let old_backup = temp_dir.path().join("tmux-sessionizer.toml.bak");
+    fs::write(&config_file, VERSION_1_CONFIG).unwrap();
+    fs::write(&old_backup, "# earlier backup\n").unwrap();
+
+    let migrated = migration::migrate_file(&TEST_FORMAT, &config_file)
+        .unwrap()
+        .expect("Version 1 file must be migrated");
+
+    assert_eq!(
+        migrated.backup,
+        temp_dir.path().join("tmux-sessionizer.toml.bak.1")
+    );
+    assert_eq!(
+        fs::read_to_string(&migrated.backup).unwrap(),
+        VERSION_1_CONFIG
+    );
+    assert_eq!(
+        fs::read_to_string(&old_backup).unwrap(),
+        "# earlier backup\n"
+    );
+}
+
+#[test]
+fn test_deprecation_warnings_cover_files_and_environment() {
+    let layer = ConfigLayer {
+        path: PathBuf::from("/config/old.toml"),
+        config: FileConfig::default(),
+        deprecations: vec![migration::Deprecation {
+            key: "new_projects_dir".to_string(),
+            replacement: "default_new_project_path".to_string(),
+            ignored: false,
+        }],
+    };
+    let lookup =
+        |name: &str| (name == "TMUX_SESSIONIZER_NEW_PROJECTS_DIR").then(|| "/new".to_string());
+
+    assert_eq!(
+        migration::deprecation_warnings(&TEST_FORMAT, &[layer], lookup),
+        vec![
+            "/config/old.toml: `new_projects_dir` is deprecated, use `default_new_project_path` instead",
+            "run `tmux-sessionizer config migrate` to update the configuration files",
+            "environment: `TMUX_SESSIONIZER_NEW_PROJECTS_DIR` is deprecated, use `TMUX_SESSIONIZER_DEFAULT_NEW_PROJECT_PATH` instead",
+        ]
+    );
+}
+
+#[test]
+fn test_loading_checks_format_version() {
+    let temp_dir = tempdir().unwrap();
+    let config_file = temp_dir.path().join("tmux-sessionizer.toml");
+
+    fs::write(
+        &config_file,
+        format!("version = {CONFIG_VERSION}\ndefault_new_project_path = \"~/new\"\n"),
+    )
+    .unwrap();
+    let (layers, errors) = collect_config_dir_layers(temp_dir.path());
+    assert!(errors.is_empty(), "{errors:?}");
+    assert!(layers[0].deprecations.is_empty());
+    assert_eq!(
+        layers[0].config.default_new_project_path.as_deref(),
+        Some("~/new")
+    );
+
+    fs::write(&config_file, format!("version = {}\n", CONFIG_VERSION + 1)).unwrap();
+    let (_, errors) = collect_config_dir_layers(temp_dir.path());
+    assert!(
+        matches!(errors[..], [ConfigError::UnsupportedVersion { .. }]),
+        "{errors:?}"
+    );
+}
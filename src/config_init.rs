@@ -1,9 +1,100 @@
+use crate::config::CONFIG_VERSION;
 use crate::error::ConfigError;
+use crate::git_repository_handler::is_git_repository;
+use crate::path_utils::PathGlob;
 use cross_xdg::BaseDirs;
 use std::fs;
-use std::path::PathBuf;
+use std::io::{BufRead, Write};
+use std::path::{Path, PathBuf};
 use tracing::{debug, info};
 
+/// Common project locations offered by the init wizard, as written to `search_paths`.
+/// Go keeps repositories at `~/go/src/<host>/<owner>/<repo>`, hence the glob.
+const CANDIDATE_SEARCH_PATHS: &[&str] = &["~/dev", "~/src", "~/code", "~/projects", "~/go/src/*/*"];
+
+/// A candidate search path that exists in the home directory.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DetectedRoot {
+    /// The search path as written to the configuration file (starting with `~`).
+    pub search_path: String,
+    /// Number of Git repositories directly inside the search path.
+    pub repositories: usize,
+}
+
+/// Returns the candidate search paths that exist below `home`, with their repository counts.
+pub fn detect_project_roots(home: &Path) -> Vec<DetectedRoot> {
+    CANDIDATE_SEARCH_PATHS
+        .iter()
+        .filter_map(|search_path| {
+            let path = home.join(search_path.trim_start_matches("~/"));
+            let directories = match PathGlob::new(&path) {
+                Ok(Some(glob)) => glob.expand(),
+                _ if path.is_dir() => vec![path],
+                _ => Vec::new(),
+            };
+            if directories.is_empty() {
+                return None;
+            }
+            let repositories = directories
+                .iter()
+                .filter_map(|directory| fs::read_dir(directory).ok())
+                .flatten()
+                .filter_map(std::result::Result::ok)
+                .filter(|entry| entry.path().is_dir() && is_git_repository(&entry.path()))
+                .count();
+            debug!(search_path, repositories, "Detected project root");
+            Some(DetectedRoot {
+                search_path: (*search_path).to_string(),
+                repositories,
+            })
+        })
+        .collect()
+}
+
+/// Parses the answer to "Include which directories?" for `count` listed roots.
+///
+/// An empty answer or `all` selects every root, `none` selects no root, and otherwise the
+/// answer is a list of 1-based numbers separated by commas or spaces. Returns the selected
+/// 0-based indices, or `None` if the answer is invalid.
+fn parse_selection(answer: &str, count: usize) -> Option<Vec<usize>> {
+    match answer.trim().to_ascii_lowercase().as_str() {
+        "" | "a" | "all" => Some((0..count).collect()),
+        "n" | "none" => Some(Vec::new()),
+        numbers => {
+            let mut selected = Vec::new();
+            for number in numbers.split([',', ' ']).filter(|part| !part.is_empty()) {
+                let index = number.parse::<usize>().ok()?.checked_sub(1)?;
+                if index >= count {
+                    return None;
+                }
+                if !selected.contains(&index) {
+                    selected.push(index);
+                }
+            }
+            Some(selected)
+        }
+    }
+}
+
+/// Returns a working configuration file that searches `roots`.
+fn generate_detected_content(roots: &[&DetectedRoot]) -> String {
+    let mut content = format!(
+        "# tmux-sessionizer configuration, generated by `tmux-sessionizer --init`.\n\
+         # See examples/tmux-sessionizer.toml in the repository for all options.\n\n\
+         version = {CONFIG_VERSION}\n\n\
+         search_paths = [\n",
+    );
+    for root in roots {
+        let search_path = toml::Value::String(root.search_path.clone());
+        content.push_str(&format!(
+            "  {search_path}, # {} Git repositories\n",
+            root.repositories
+        ));
+    }
+    content.push_str("]\n");
+    content
+}
+
 #[derive(Debug)]
 pub struct ConfigInitializer {
     config_dir: PathBuf,
@@ -53,27 +144,99 @@ impl ConfigInitializer {
         Ok(())
     }
 
+    /// Creates the configuration file from the project roots detected in the home directory.
+    ///
+    /// With `input` and `output`, the detected roots are listed and the user is asked which
+    /// ones to include; otherwise every detected root is included. If nothing is detected or
+    /// selected, the commented template is written instead.
+    pub fn init_detected_config(
+        &self,
+        home: &Path,
+        prompt: Option<(&mut dyn BufRead, &mut dyn Write)>,
+    ) -> Result<bool, ConfigError> {
+        self.create_config_directory()?;
+        if self.config_file.exists() {
+            info!("Config file already exists: {}", self.config_file.display());
+            return Ok(false);
+        }
+
+        let roots = detect_project_roots(home);
+        let selected: Vec<&DetectedRoot> = match prompt {
+            Some((input, output)) if !roots.is_empty() => {
+                Self::ask_for_roots(&roots, input, output).map_err(ConfigError::PromptFailed)?
+            }
+            _ => roots.iter().collect(),
+        };
+
+        let file_was_created = if selected.is_empty() {
+            info!("No project directories selected, writing the config template");
+            self.create_template_file()?
+        } else {
+            info!(
+                count = selected.len(),
+                "Writing config with detected project directories"
+            );
+            self.write_config_file(&generate_detected_content(&selected))?
+        };
+        self.validate_created_file()?;
+        Ok(file_was_created)
+    }
+
+    fn ask_for_roots<'a>(
+        roots: &'a [DetectedRoot],
+        input: &mut dyn BufRead,
+        output: &mut dyn Write,
+    ) -> std::io::Result<Vec<&'a DetectedRoot>> {
+        writeln!(output, "Found project directories:")?;
+        for (number, root) in roots.iter().enumerate() {
+            writeln!(
+                output,
+                "  {}) {} ({} Git repositories)",
+                number + 1,
+                root.search_path,
+                root.repositories
+            )?;
+        }
+        loop {
+            write!(
+                output,
+                "Include which directories? [all] (e.g. 1,3 or none): "
+            )?;
+            output.flush()?;
+            let mut answer = String::new();
+            if input.read_line(&mut answer)? == 0 {
+                // End of input: keep the default
+                answer.clear();
+            }
+            match parse_selection(&answer, roots.len()) {
+                Some(indices) => {
+                    return Ok(indices.into_iter().map(|index| &roots[index]).collect());
+                }
+                None => writeln!(
+                    output,
+                    "Please enter numbers between 1 and {}, \"all\" or \"none\".",
+                    roots.len()
+                )?,
+            }
+        }
+    }
+
     fn create_template_file(&self) -> Result<bool, ConfigError> {
+        self.write_config_file(&Self::generate_template_content())
+    }
+
+    fn write_config_file(&self, content: &str) -> Result<bool, ConfigError> {
         if self.config_file.exists() {
             info!("Config file already exists: {}", self.config_file.display());
             return Ok(false); // File was not created, it already existed
         }
 
-        debug!(
-            "Creating config template file: {}",
-            self.config_file.display()
-        );
-        let template_content = Self::generate_template_content();
-        fs::write(&self.config_file, template_content).map_err(|e| {
-            ConfigError::TemplateWriteFailed {
-                path: self.config_file.clone(),
-                source: e,
-            }
+        debug!("Creating config file: {}", self.config_file.display());
+        fs::write(&self.config_file, content).map_err(|e| ConfigError::TemplateWriteFailed {
+            path: self.config_file.clone(),
+            source: e,
         })?;
-        info!(
-            "Created config template file: {}",
-            self.config_file.display()
-        );
+        info!("Created config file: {}", self.config_file.display());
         Ok(true) // File was created
     }
 
@@ -87,6 +250,13 @@ impl ConfigInitializer {
 # The configuration file should be placed at:
 # ~/.config/tmux-sessionizer/tmux-sessionizer.toml
 
+# --- Format Version ---
+#
+# `version` is the version of the configuration format this file is written in. Files without
+# it are version 1. Files of an older version still load, with a warning for every renamed
+# key; `tmux-sessionizer config migrate` rewrites them in the current format.
+version = {CONFIG_VERSION}
+
 # --- Search Paths ---
 #
 # `search_paths` defines the primary directories where tmux-sessionizer will look for projects.
@@ -101,6 +271,21 @@ impl ConfigInitializer {
 #
 # Example: Search only in a specific project directory
 # search_paths = ["/path/to/my/projects"]
+#
+# All path settings may use environment variables: `$VAR`, `${VAR}` and
+# `${VAR:-default}` (used when VAR is unset or empty), as well as `~user` for another
+# user's home directory. An undefined variable without a default is a configuration error.
+#
+# Example: Share one configuration between machines with different workspace roots
+# search_paths = ["${WORKSPACE:-~/dev}/projects"]
+#
+# Paths in `search_paths` and `additional_paths` may contain glob wildcards: `*`, `?` and
+# `[...]` match within one directory name, `**` matches up to 8 levels of directories and
+# does not descend into symbolic links. Every matching directory is used as if it had been
+# listed. Wildcards skip hidden directories.
+#
+# Example: Search every client directory in ~/work and every `services` directory below ~/src
+# search_paths = ["~/work/*/", "~/src/**/services"]
 
 
 # --- Default New Project Path ---
@@ -164,7 +349,158 @@ impl ConfigInitializer {
 #   "/target/",
 #   "~/dev/legacy-project", # Exclude a specific project in the dev directory
 # ]
-"#.to_string()
+
+
+# --- Exclusion Globs ---
+#
+# `exclude_globs` is a friendlier alternative to `exclude_patterns`: a list of glob patterns
+# matched against the *full path* of each directory. `*` and `?` also match '/', so
+# "*/archive/*" excludes everything inside any `archive` directory. Globs may start with '~'.
+#
+# Example: Exclude archived projects and everything below ~/dev/legacy
+# exclude_globs = [
+#   "**/*-archive",
+#   "~/dev/legacy/*",
+# ]
+
+
+# --- Session Matching ---
+#
+# `session_match` controls how tmux-sessionizer finds an already running session for the
+# selected project. Sessions can be found by their generated name or by their working
+# directory (`session_path`), which also catches sessions that were created manually or
+# renamed after creation. Both lookups are always tried; this setting picks which one runs first.
+#
+# Accepted values: "name" (default) or "path".
+#
+# Example: Prefer sessions whose working directory is the selected project
+# session_match = "path"
+
+
+# --- Session Environment ---
+#
+# `env` is a table of environment variables that are set in every newly created session
+# (passed to `tmux new-session -e`), so they are visible in the first pane and in every
+# window opened later in that session.
+#
+# Example: Set an editor and a default log level for all project sessions
+# [env]
+# EDITOR = "nvim"
+# RUST_LOG = "info"
+#
+# `direnv` exports the environment evaluated by direnv from the project's `.envrc` into
+# the session when it is created. The `.envrc` must already be allowed (`direnv allow`).
+# Defaults to false.
+#
+# direnv = true
+#
+# A project can also set its own variables in a `.tmux-sessionizer.toml` file in the
+# project directory. Project variables override direnv, which overrides the `env` table above:
+#
+# # ~/dev/my-project/.tmux-sessionizer.toml
+# [env]
+# DATABASE_URL = "postgres://localhost/my_project"
+
+
+# --- Hooks ---
+#
+# Commands run when sessions are created or switched to. All hooks are optional.
+# - pre_create: shell command run in the project directory before a new session is created
+# - on_create:  command typed into the first pane of a newly created session
+# - on_switch:  shell command run in the project directory before switching to an existing session
+#
+# Commands can use the {path}, {session} and {branch} placeholders, which are replaced
+# with shell-quoted values, so do not put them in quotes. A failing hook only prints a
+# warning. A project-local `.tmux-sessionizer.toml` can override individual hooks.
+#
+# Example: Fetch before creating a session and open the editor in it
+# [hooks]
+# pre_create = "git fetch --quiet"
+# on_create = "nvim ."
+# on_switch = "echo switching to {session} on {branch}"
+
+
+# --- tmuxinator / tmuxp Layouts ---
+#
+# Project files from tmuxinator (~/.config/tmuxinator, ~/.tmuxinator) and tmuxp (~/.tmuxp)
+# are imported by default. Their root directories are listed in the finder, and new
+# sessions for those directories are created with the windows, panes and commands from
+# the file. Set to false to ignore these files.
+#
+# import_layouts = false
+
+
+# --- Finder ---
+#
+# Settings of the fuzzy finder. `view` lists projects "flat" (default), or as a "tree"
+# grouped by the search path they were found in, with the worktrees of a repository
+# indented beneath it. `--view` overrides it for one run.
+#
+# `prompt`, `height` (lines or a percentage), `layout` ("default" puts the query at the
+# bottom, "reverse" at the top), `color` (skim --color syntax), `header` and `border`
+# (draw a border around the finder) change how the finder looks.
+#
+# `icons` shows badges before each project for its type (repo, worktree, bare, plain),
+# its language (from files such as Cargo.toml or package.json) and a running session:
+# "none" (default), "ascii", or "nerd" for Nerd Font glyphs.
+#
+# `bindings` maps keys to actions on the highlighted project: "open", "open-detached"
+# (create the session without switching to it, also bound to ctrl-o), "new-worktree"
+# (ask for a branch and create a worktree for it next to the repository),
+# "kill-session" and "copy-path" (copy the path to a tmux paste buffer and the
+# clipboard).
+#
+# [finder]
+# view = "tree"
+# height = "60%"
+# color = "light"
+# header = "ctrl-o: open in background, ctrl-d: kill session"
+# border = true
+# icons = "ascii"
+#
+# [finder.bindings]
+# ctrl-w = "new-worktree"
+# ctrl-d = "kill-session"
+# ctrl-y = "copy-path"
+
+
+# --- Profiles ---
+#
+# Profiles are named sets of settings applied on top of the settings above. Select one
+# with `--profile <name>` or the TMUX_SESSIONIZER_PROFILE environment variable, or press
+# alt-p in the finder to switch to the next profile.
+#
+# A profile can set search_paths, additional_paths, exclude_patterns, exclude_globs,
+# default_new_project_path, env and hooks. Paths, patterns and the new project path
+# replace the global values, env variables are added, and hooks replace the global
+# hook of the same name.
+#
+# Example: Separate work and open source projects
+# [profiles.work]
+# search_paths = ["~/work"]
+# default_new_project_path = "~/work"
+#
+# [profiles.work.env]
+# AWS_PROFILE = "work"
+#
+# [profiles.oss]
+# search_paths = ["~/oss"]
+# exclude_patterns = ["/vendor/"]
+
+
+# --- Includes ---
+#
+# `include` lists configuration files merged before this file, so that a shared base
+# configuration (e.g., from a dotfiles repository) can be combined with local settings.
+# Relative paths are resolved against this file's directory.
+#
+# include = ["~/dotfiles/tmux-sessionizer/base.toml"]
+#
+# Every `*.toml` file in ~/.config/tmux-sessionizer/conf.d/ is merged after this file,
+# in file name order. Later values replace earlier ones; `env`, `profiles` and `hooks`
+# are merged entry by entry.
+"#
+        .replace("{CONFIG_VERSION}", &CONFIG_VERSION.to_string())
     }
 
     fn validate_created_file(&self) -> Result<(), ConfigError> {
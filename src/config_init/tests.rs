@@ -205,6 +205,22 @@ fn test_generate_template_content() {
     }
 }
 
+#[test]
+fn test_generate_template_content_is_current_format() {
+    let content = ConfigInitializer::generate_template_content();
+    let file_config: crate::config::FileConfig =
+        toml::from_str(&content).expect("Template must be a valid configuration");
+    assert_eq!(file_config.version, Some(CONFIG_VERSION));
+}
+
+#[test]
+fn test_example_file_matches_template() {
+    assert_eq!(
+        include_str!("../../examples/tmux-sessionizer.toml"),
+        ConfigInitializer::generate_template_content()
+    );
+}
+
 #[test]
 fn test_init_config_full_workflow() {
     // Use a more specific temp directory to avoid conflicts
@@ -411,3 +427,114 @@ fn test_config_initializer_new_xdg_error() {
     }
     // If it succeeds, that's fine too - the system has fallbacks
 }
+
+fn setup_home_with_projects() -> tempfile::TempDir {
+    let home = tempdir().unwrap();
+    for repo in ["dev/shop", "dev/blog", "go/src/github.com/me/tool"] {
+        git2::Repository::init(home.path().join(repo)).unwrap();
+    }
+    fs::create_dir_all(home.path().join("dev/notes")).unwrap();
+    fs::create_dir_all(home.path().join("code")).unwrap();
+    home
+}
+
+fn initializer_in(dir: &Path) -> ConfigInitializer {
+    let config_dir = dir.join("tmux-sessionizer");
+    ConfigInitializer {
+        config_file: config_dir.join("tmux-sessionizer.toml"),
+        config_dir,
+    }
+}
+
+#[test]
+fn test_detect_project_roots_counts_repositories() {
+    let home = setup_home_with_projects();
+
+    let roots = detect_project_roots(home.path());
+
+    assert_eq!(
+        roots,
+        vec![
+            DetectedRoot {
+                search_path: "~/dev".to_string(),
+                repositories: 2,
+            },
+            DetectedRoot {
+                search_path: "~/code".to_string(),
+                repositories: 0,
+            },
+            DetectedRoot {
+                search_path: "~/go/src/*/*".to_string(),
+                repositories: 1,
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_parse_selection() {
+    assert_eq!(parse_selection("\n", 3), Some(vec![0, 1, 2]));
+    assert_eq!(parse_selection("All", 2), Some(vec![0, 1]));
+    assert_eq!(parse_selection("none", 2), Some(vec![]));
+    assert_eq!(parse_selection("3, 1 3", 3), Some(vec![2, 0]));
+    assert_eq!(parse_selection("4", 3), None);
+    assert_eq!(parse_selection("0", 3), None);
+    assert_eq!(parse_selection("dev", 3), None);
+}
+
+#[test]
+fn test_init_detected_config_asks_which_roots_to_include() {
+    let home = setup_home_with_projects();
+    let config_dir = tempdir().unwrap();
+    let initializer = initializer_in(config_dir.path());
+    let mut input = std::io::Cursor::new("7\n1,3\n");
+    let mut output = Vec::new();
+
+    let created = initializer
+        .init_detected_config(home.path(), Some((&mut input, &mut output)))
+        .unwrap();
+
+    assert!(created);
+    let output = String::from_utf8(output).unwrap();
+    assert!(output.contains("  1) ~/dev (2 Git repositories)"));
+    assert!(output.contains("Please enter numbers between 1 and 3"));
+    let content = fs::read_to_string(initializer.config_file()).unwrap();
+    let file_config: crate::config::FileConfig = toml::from_str(&content).unwrap();
+    assert_eq!(
+        file_config.search_paths,
+        Some(vec!["~/dev".to_string(), "~/go/src/*/*".to_string()])
+    );
+}
+
+#[test]
+fn test_init_detected_config_without_prompt_includes_all_roots() {
+    let home = setup_home_with_projects();
+    let config_dir = tempdir().unwrap();
+    let initializer = initializer_in(config_dir.path());
+
+    assert!(initializer.init_detected_config(home.path(), None).unwrap());
+
+    let content = fs::read_to_string(initializer.config_file()).unwrap();
+    let file_config: crate::config::FileConfig = toml::from_str(&content).unwrap();
+    assert_eq!(file_config.search_paths.map(|paths| paths.len()), Some(3));
+    assert_eq!(file_config.version, Some(CONFIG_VERSION));
+
+    // An existing file is left alone
+    assert!(!initializer.init_detected_config(home.path(), None).unwrap());
+}
+
+#[test]
+fn test_init_detected_config_falls_back_to_template() {
+    let empty_home = tempdir().unwrap();
+    let config_dir = tempdir().unwrap();
+    let initializer = initializer_in(config_dir.path());
+
+    assert!(
+        initializer
+            .init_detected_config(empty_home.path(), None)
+            .unwrap()
+    );
+
+    let content = fs::read_to_string(initializer.config_file()).unwrap();
+    assert!(content.contains("Example configuration file for tmux-sessionizer"));
+}
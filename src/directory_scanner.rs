@@ -6,11 +6,12 @@
 //! and exclusion patterns. Parallel processing is used via Rayon to speed up the scanning
 //! of multiple candidate paths.
 
-use crate::config::Config;
+use crate::config::{Config, ProjectType};
 use crate::container_detector;
 use crate::error::Result;
 use crate::git_repository_handler::{self, is_git_repository, list_linked_worktrees};
-use crate::path_utils::expand_tilde;
+use crate::path_utils::{PathGlob, expand_tilde};
+use crate::session_manager::SessionManager;
 use git2::Repository;
 use rayon::prelude::*;
 use std::collections::HashSet;
@@ -53,6 +54,92 @@ pub struct DirectoryEntry {
     pub parent_path: Option<PathBuf>,
 }
 
+impl DirectoryEntry {
+    /// Returns the type of the entry as selected with `--type` and printed with `--print=json`.
+    pub fn project_type(&self) -> ProjectType {
+        match self.entry_type {
+            DirectoryType::GitRepository => ProjectType::Git,
+            DirectoryType::GitWorktree { .. } => ProjectType::Worktree,
+            DirectoryType::Plain | DirectoryType::GitWorktreeContainer => ProjectType::Plain,
+        }
+    }
+
+    /// Returns `true` if one of `running_sessions` belongs to this entry.
+    ///
+    /// # Arguments
+    ///
+    /// * `running_sessions` - The name and canonical working directory of every running
+    ///   session. A session belongs to the entry if it has the entry's path or its generated
+    ///   session name.
+    pub fn has_running_session(&self, running_sessions: &[(String, PathBuf)]) -> bool {
+        let session_name =
+            SessionManager::generate_session_name(&self.resolved_path, self.parent_path.as_deref());
+        running_sessions
+            .iter()
+            .any(|(name, path)| *name == session_name || *path == self.resolved_path)
+    }
+}
+
+/// Criteria narrowing the scanned entries offered for selection, set with `--type`, `--repo`
+/// and `--running`.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct EntryFilter {
+    /// Only keep entries of these types, or of any type if empty.
+    pub types: Vec<ProjectType>,
+    /// Only keep the worktrees of the repository with this directory name.
+    pub repo: Option<String>,
+    /// Only keep entries that have a running tmux session.
+    pub running: bool,
+}
+
+impl EntryFilter {
+    /// Returns `true` if the filter keeps every entry.
+    pub fn is_empty(&self) -> bool {
+        self.types.is_empty() && self.repo.is_none() && !self.running
+    }
+
+    /// Returns the entries of `entries` that match every criterion of the filter.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - The scanned entries.
+    /// * `running_sessions` - The name and canonical working directory of every running
+    ///   session, see [`DirectoryEntry::has_running_session`].
+    pub fn apply(
+        &self,
+        entries: Vec<DirectoryEntry>,
+        running_sessions: &[(String, PathBuf)],
+    ) -> Vec<DirectoryEntry> {
+        entries
+            .into_iter()
+            .filter(|entry| self.matches(entry, running_sessions))
+            .collect()
+    }
+
+    /// Returns `true` if `entry` matches every criterion of the filter.
+    fn matches(&self, entry: &DirectoryEntry, running_sessions: &[(String, PathBuf)]) -> bool {
+        if !self.types.is_empty() && !self.types.contains(&entry.project_type()) {
+            return false;
+        }
+
+        if let Some(repo) = &self.repo {
+            let DirectoryType::GitWorktree { main_worktree_path } = &entry.entry_type else {
+                return false;
+            };
+            let Some(repo_dir) = main_worktree_path.file_name() else {
+                return false;
+            };
+            // Bare repositories are often named `<repo>.git`
+            let repo_dir = repo_dir.to_string_lossy();
+            if repo_dir != *repo && repo_dir.strip_suffix(".git") != Some(repo.as_str()) {
+                return false;
+            }
+        }
+
+        !self.running || entry.has_running_session(running_sessions)
+    }
+}
+
 /// Scans the filesystem for directories based on the provided configuration.
 ///
 /// It identifies plain directories, Git repositories, and Git worktrees,
@@ -201,6 +288,12 @@ impl<'a> DirectoryScanner<'a> {
                 return Ok(Vec::new()); // Return empty vec for skip
             }
         }
+        for glob in &self.config.exclude_globs {
+            if glob.matches_path(&original_path) || glob.matches_path(&resolved_path) {
+                debug!(path = %resolved_path.display(), glob = %glob, "Skipping path excluded by glob");
+                return Ok(Vec::new()); // Return empty vec for skip
+            }
+        }
 
         let basename_of_resolved_path = resolved_path.file_name().map_or_else(
             || {
@@ -377,12 +470,83 @@ impl<'a> DirectoryScanner<'a> {
         }
     }
 
+    /// Returns `paths` followed by the directories currently matching `globs`.
+    fn with_glob_matches(paths: &[PathBuf], globs: &[PathGlob]) -> Vec<PathBuf> {
+        paths
+            .iter()
+            .cloned()
+            .chain(globs.iter().flat_map(PathGlob::expand))
+            .collect()
+    }
+
+    /// Classifies a single directory outside the scan, for a direct selection given as a path.
+    ///
+    /// The directory is processed like an additional path, so Git repositories and worktrees
+    /// get the same entry as when they are found by a scan. A directory the scan would skip
+    /// (e.g., because it is excluded or is a worktree container) is returned as a plain
+    /// directory, since it was asked for explicitly.
+    ///
+    /// # Arguments
+    ///
+    /// * `path` - The path of the directory, absolute or relative to the working directory.
+    ///
+    /// # Returns
+    ///
+    /// The `DirectoryEntry` of the directory, or `None` if `path` is not an existing directory.
+    pub fn classify_path(&self, path: &Path) -> Option<DirectoryEntry> {
+        let resolved_path = fs::canonicalize(path).ok().filter(|p| p.is_dir())?;
+        let entries = self
+            .process_path_candidate(path.to_path_buf(), true, &Mutex::new(HashSet::new()))
+            .unwrap_or_else(|e| {
+                warn!(path = %path.display(), error = %e, "Failed to classify path, treating as plain directory");
+                Vec::new()
+            });
+        let entry = entries
+            .into_iter()
+            .find(|entry| entry.resolved_path == resolved_path)
+            .unwrap_or_else(|| {
+                debug!(path = %resolved_path.display(), "Path skipped by the scan rules, adding as plain directory");
+                let display_name = resolved_path.file_name().map_or_else(
+                    || resolved_path.display().to_string(),
+                    |name| name.to_string_lossy().into_owned(),
+                );
+                Self::add_plain_directory_entry(path.to_path_buf(), resolved_path, display_name)
+            });
+        Some(entry)
+    }
+
+    /// Returns the directories whose children are scanned: `config.search_paths` and the
+    /// directories matching `config.search_globs`, with tildes expanded. Search paths that are
+    /// not directories are skipped with a warning.
+    pub fn search_roots(&self) -> Vec<PathBuf> {
+        Self::with_glob_matches(&self.config.search_paths, &self.config.search_globs)
+            .iter()
+            .filter_map(|search_path_config_entry| {
+                let path_span = span!(Level::DEBUG, "collect_search_root", config_path = %search_path_config_entry.display());
+                let _path_enter = path_span.enter();
+
+                let Some(search_path_base) = expand_tilde(search_path_config_entry) else {
+                    warn!(path = %search_path_config_entry.display(), "Could not expand tilde for search path, skipping");
+                    return None;
+                };
+                debug!(expanded_path = %search_path_base.display(), "Expanded search path");
+
+                if !search_path_base.is_dir() {
+                    warn!(path = %search_path_base.display(), "Search path is not a directory or is inaccessible, skipping");
+                    return None;
+                }
+                Some(search_path_base)
+            })
+            .collect()
+    }
+
     /// Performs the directory scan based on the configuration.
     ///
     /// This is the main entry point for the scanner. It:
     /// 1. Initializes data structures (path list, processed paths set).
     /// 2. Collects initial paths from `config.search_paths` (non-recursive children)
-    ///    and `config.additional_paths`. Handles tilde expansion.
+    ///    and `config.additional_paths`, including the directories matching their globs.
+    ///    Handles tilde expansion.
     /// 3. Uses Rayon to process the collected paths in parallel via `process_path_candidate`.
     /// 4. Consolidates the results from parallel processing.
     /// 5. Returns the final list of unique `DirectoryEntry` items.
@@ -399,21 +563,7 @@ impl<'a> DirectoryScanner<'a> {
         let mut paths_to_process: Vec<(PathBuf, bool)> = Vec::new(); // (path, is_explicitly_added)
 
         debug!(search_paths = ?self.config.search_paths, "Collecting paths from search_paths");
-        for search_path_config_entry in &self.config.search_paths {
-            let path_span = span!(Level::DEBUG, "collect_search_root", config_path = %search_path_config_entry.display());
-            let _path_enter = path_span.enter();
-
-            let Some(search_path_base) = expand_tilde(search_path_config_entry) else {
-                warn!(path = %search_path_config_entry.display(), "Could not expand tilde for search path, skipping");
-                continue;
-            };
-            debug!(expanded_path = %search_path_base.display(), "Expanded search path");
-
-            if !search_path_base.is_dir() {
-                warn!(path = %search_path_base.display(), "Search path is not a directory or is inaccessible, skipping");
-                continue;
-            }
-
+        for search_path_base in self.search_roots() {
             debug!(path = %search_path_base.display(), "Collecting direct children for parallel processing");
             paths_to_process.extend(
                 WalkDir::new(&search_path_base)
@@ -435,7 +585,9 @@ impl<'a> DirectoryScanner<'a> {
         }
 
         debug!(additional_paths = ?self.config.additional_paths, "Collecting additional paths");
-        for additional_path_config_entry in &self.config.additional_paths {
+        let additional_paths =
+            Self::with_glob_matches(&self.config.additional_paths, &self.config.additional_globs);
+        for additional_path_config_entry in &additional_paths {
             let path_span = span!(Level::DEBUG, "collect_additional_path", config_path = %additional_path_config_entry.display());
             let _path_enter = path_span.enter();
 
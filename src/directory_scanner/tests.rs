@@ -990,3 +990,206 @@ fn test_search_path_is_git_repo_itself() {
     );
     assert_entry_properties(&entries_additional, "my_repo", "GitRepository", "my_repo");
 }
+
+#[test]
+fn test_scan_expands_search_path_globs() {
+    let temp_dir = tempdir().unwrap();
+    for dir in ["work/client-a/api", "work/client-b/web", "work/notes"] {
+        fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+    }
+
+    let mut config = default_test_config();
+    let glob = temp_dir.path().join("work/client-*/");
+    config.search_globs = vec![PathGlob::new(&glob).unwrap().unwrap()];
+
+    let scanner = DirectoryScanner::new(&config);
+    let mut entries = scanner.scan();
+    entries.sort_by(|a, b| a.resolved_path.cmp(&b.resolved_path));
+
+    assert_eq!(entries.len(), 2, "Entries: {entries:?}");
+    assert_entry_properties(&entries, "client-a/api", "Plain", "api");
+    assert_entry_properties(&entries, "client-b/web", "Plain", "web");
+}
+
+#[test]
+fn test_search_roots_expand_globs_and_skip_missing_paths() {
+    let temp_dir = tempdir().unwrap();
+    for dir in ["dev", "work/client-a", "work/client-b"] {
+        fs::create_dir_all(temp_dir.path().join(dir)).unwrap();
+    }
+
+    let mut config = default_test_config();
+    config.search_paths = vec![temp_dir.path().join("dev"), temp_dir.path().join("missing")];
+    let glob = temp_dir.path().join("work/client-*/");
+    config.search_globs = vec![PathGlob::new(&glob).unwrap().unwrap()];
+
+    let mut roots = DirectoryScanner::new(&config).search_roots();
+    roots.sort();
+    assert_eq!(
+        roots,
+        vec![
+            temp_dir.path().join("dev"),
+            temp_dir.path().join("work/client-a"),
+            temp_dir.path().join("work/client-b"),
+        ]
+    );
+}
+
+#[test]
+fn test_scan_with_exclude_glob() {
+    let temp_dir = tempdir().unwrap();
+    fs::create_dir(temp_dir.path().join("project1")).unwrap();
+    fs::create_dir(temp_dir.path().join("project1-archive")).unwrap();
+    fs::create_dir(temp_dir.path().join("project2")).unwrap();
+
+    let mut config = default_test_config();
+    config.search_paths = vec![temp_dir.path().to_path_buf()];
+    config.exclude_globs = vec![glob::Pattern::new("**/*-archive").unwrap()];
+
+    let scanner = DirectoryScanner::new(&config);
+    let entries = scanner.scan();
+
+    assert_eq!(entries.len(), 2, "Entries: {entries:?}");
+    assert!(
+        !entries
+            .iter()
+            .any(|e| e.resolved_path.ends_with("project1-archive"))
+    );
+}
+
+#[test]
+fn test_classify_path_outside_search_paths() {
+    let temp_dir = tempdir().unwrap();
+    let plain_path = temp_dir.path().join("plain");
+    fs::create_dir(&plain_path).unwrap();
+    let repo_path = temp_dir.path().join("repo");
+    init_repo(&repo_path);
+    let worktree_path = temp_dir.path().join("worktrees").join("wt1");
+    add_worktree_to_standard_repo(&repo_path, "wt1", &worktree_path);
+
+    let config = default_test_config();
+    let scanner = DirectoryScanner::new(&config);
+
+    let plain = scanner.classify_path(&plain_path).unwrap();
+    assert_eq!(plain.entry_type, DirectoryType::Plain);
+    assert_eq!(plain.display_name, "plain");
+
+    let repo = scanner.classify_path(&repo_path).unwrap();
+    assert_eq!(repo.entry_type, DirectoryType::GitRepository);
+    assert_eq!(repo.resolved_path, fs::canonicalize(&repo_path).unwrap());
+
+    let worktree = scanner.classify_path(&worktree_path).unwrap();
+    assert_eq!(worktree.display_name, "[repo] wt1");
+    assert_eq!(
+        worktree.parent_path,
+        Some(fs::canonicalize(&repo_path).unwrap())
+    );
+
+    assert!(
+        scanner
+            .classify_path(&temp_dir.path().join("missing"))
+            .is_none()
+    );
+    assert!(
+        scanner
+            .classify_path(&repo_path.join("initial_file.txt"))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_classify_path_ignores_exclusions() {
+    let temp_dir = tempdir().unwrap();
+    let archived_path = temp_dir.path().join("project-archive");
+    fs::create_dir(&archived_path).unwrap();
+
+    let mut config = default_test_config();
+    config.exclude_globs = vec![glob::Pattern::new("**/*-archive").unwrap()];
+    let scanner = DirectoryScanner::new(&config);
+
+    let entry = scanner.classify_path(&archived_path).unwrap();
+    assert_eq!(entry.entry_type, DirectoryType::Plain);
+    assert_eq!(entry.display_name, "project-archive");
+}
+
+fn filter_test_entries() -> Vec<DirectoryEntry> {
+    let worktree = |repo: &str, name: &str| DirectoryEntry {
+        path: PathBuf::from(format!("/dev/worktrees/{name}")),
+        resolved_path: PathBuf::from(format!("/dev/worktrees/{name}")),
+        display_name: format!("[{repo}] {name}"),
+        entry_type: DirectoryType::GitWorktree {
+            main_worktree_path: PathBuf::from(format!("/dev/{repo}")),
+        },
+        parent_path: Some(PathBuf::from(format!("/dev/{repo}"))),
+    };
+    vec![
+        DirectoryEntry {
+            path: PathBuf::from("/dev/api"),
+            resolved_path: PathBuf::from("/dev/api"),
+            display_name: "api".to_string(),
+            entry_type: DirectoryType::GitRepository,
+            parent_path: None,
+        },
+        worktree("api", "feature"),
+        worktree("web.git", "main"),
+        DirectoryEntry {
+            path: PathBuf::from("/dev/notes"),
+            resolved_path: PathBuf::from("/dev/notes"),
+            display_name: "notes".to_string(),
+            entry_type: DirectoryType::Plain,
+            parent_path: None,
+        },
+    ]
+}
+
+fn display_names(entries: &[DirectoryEntry]) -> Vec<&str> {
+    entries.iter().map(|e| e.display_name.as_str()).collect()
+}
+
+#[test]
+fn test_entry_filter_by_type() {
+    let filter = EntryFilter {
+        types: vec![ProjectType::Git, ProjectType::Plain],
+        ..EntryFilter::default()
+    };
+    let entries = filter.apply(filter_test_entries(), &[]);
+    assert_eq!(display_names(&entries), vec!["api", "notes"]);
+
+    assert!(EntryFilter::default().is_empty());
+    assert_eq!(
+        EntryFilter::default()
+            .apply(filter_test_entries(), &[])
+            .len(),
+        4
+    );
+}
+
+#[test]
+fn test_entry_filter_by_repo() {
+    let filter = |repo: &str| EntryFilter {
+        repo: Some(repo.to_string()),
+        ..EntryFilter::default()
+    };
+    let entries = filter("api").apply(filter_test_entries(), &[]);
+    assert_eq!(display_names(&entries), vec!["[api] feature"]);
+
+    // A bare repository matches with or without its `.git` suffix
+    let entries = filter("web").apply(filter_test_entries(), &[]);
+    assert_eq!(display_names(&entries), vec!["[web.git] main"]);
+    let entries = filter("web.git").apply(filter_test_entries(), &[]);
+    assert_eq!(display_names(&entries), vec!["[web.git] main"]);
+}
+
+#[test]
+fn test_entry_filter_running_by_name_or_path() {
+    let filter = EntryFilter {
+        running: true,
+        ..EntryFilter::default()
+    };
+    let running_sessions = vec![
+        ("api_feature".to_string(), PathBuf::from("/elsewhere")),
+        ("renamed".to_string(), PathBuf::from("/dev/notes")),
+    ];
+    let entries = filter.apply(filter_test_entries(), &running_sessions);
+    assert_eq!(display_names(&entries), vec!["[api] feature", "notes"]);
+}
@@ -30,6 +30,52 @@ pub enum ConfigError {
         source: regex::Error,
     },
 
+    #[error("Invalid glob pattern '{pattern}' in configuration: {source}")]
+    InvalidGlob {
+        pattern: String,
+        source: glob::PatternError,
+    },
+
+    #[error("Invalid value '{value}' for environment variable {name}: expected {expected}")]
+    InvalidEnvVar {
+        name: String,
+        value: String,
+        expected: &'static str,
+    },
+
+    #[error(
+        "Configuration file '{path}' has version {version}, but only versions 1 to {supported} are supported"
+    )]
+    UnsupportedVersion {
+        path: PathBuf,
+        version: i64,
+        supported: u32,
+    },
+
+    #[error("Failed to parse configuration file '{path}' for migration: {source}")]
+    MigrationParseError {
+        path: PathBuf,
+        source: Box<toml_edit::TomlError>,
+    },
+
+    #[error("Failed to write migrated configuration file '{path}': {source}")]
+    MigrationWriteFailed { path: PathBuf, source: io::Error },
+
+    #[error("Configuration file '{path}' includes itself")]
+    IncludeCycle { path: PathBuf },
+
+    #[error("Unknown profile '{name}' (available profiles: {available})")]
+    UnknownProfile { name: String, available: String },
+
+    #[error("Found {count} configuration problem(s)")]
+    Invalid { count: usize },
+
+    #[error("Cannot expand path '{path}' in configuration: {source}")]
+    PathExpansion {
+        path: String,
+        source: PathExpansionError,
+    },
+
     #[error("Path validation failed: {0}")]
     InvalidPath(#[from] PathValidationError),
 
@@ -39,6 +85,9 @@ pub enum ConfigError {
     #[error("Failed to write config template: {path}")]
     TemplateWriteFailed { path: PathBuf, source: io::Error },
 
+    #[error("Failed to read the answer from the terminal: {0}")]
+    PromptFailed(io::Error),
+
     #[error("Config file validation failed: {path}")]
     ValidationFailed {
         path: PathBuf,
@@ -61,6 +110,21 @@ pub enum PathValidationError {
     FilesystemError { path: PathBuf, source: io::Error },
 }
 
+#[derive(Debug, Error)]
+pub enum PathExpansionError {
+    #[error("environment variable {name} is not set")]
+    UndefinedVariable { name: String },
+
+    #[error("unknown user '{name}'")]
+    UnknownUser { name: String },
+
+    #[error("the home directory could not be determined")]
+    NoHomeDirectory,
+
+    #[error("a '${{' is not closed with '}}'")]
+    UnterminatedVariable,
+}
+
 /// The primary error type used throughout the `tmux-sessionizer` application.
 ///
 /// This enum consolidates various error kinds that can occur during the application's
@@ -98,6 +162,14 @@ pub enum AppError {
     #[error("Session management error: {0}")]
     Session(String),
 
+    /// Errors related to reading tmuxinator or tmuxp session layout files.
+    #[error("Session layout error: {0}")]
+    Layout(String),
+
+    /// Errors related to installing the tmux key bindings (e.g., mismatched marker comments).
+    #[error("Key bindings error: {0}")]
+    Keybindings(String),
+
     /// Errors originating from executing tmux commands via the `tmux_interface` crate.
     /// Wraps `tmux_interface::Error`.
     #[error("Tmux command error: {0}")]
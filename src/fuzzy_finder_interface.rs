@@ -2,20 +2,137 @@
 //! (using the `skim` library) or by direct matching based on user input.
 //!
 //! This module provides the `FuzzyFinder` struct and associated methods to:
-//! - Format directory entries for display.
-//! - Prepare input for the `skim` fuzzy finder.
+//! - Format directory entries for display, optionally with badges for their type, language
+//!   and running session.
+//! - Prepare input for the `skim` fuzzy finder, as a flat list or as a tree of directories,
+//!   repositories and worktrees.
 //! - Run the `skim` interface and process user selection.
-//! - Implement direct selection logic based on various matching strategies.
+//! - Implement direct selection logic based on various matching strategies, with the
+//!   finder's fuzzy matcher as a fallback.
 //! - Define the `SelectedItem` struct to represent the user's choice.
 
-use crate::directory_scanner::DirectoryEntry;
+use crate::config::{FinderAction, FinderConfig, FinderIcons, FinderLayout, FinderView};
+use crate::directory_scanner::{DirectoryEntry, DirectoryType};
 use crate::error::{AppError, Result};
+use fuzzy_matcher::FuzzyMatcher;
+use fuzzy_matcher::skim::SkimMatcherV2;
 use skim::prelude::*;
+use std::collections::{BTreeMap, HashSet};
 use std::fs;
 use std::io::Cursor;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 use tracing::{debug, warn};
 
+/// Key that switches to the previously used project session instead of the highlighted entry.
+const LAST_SESSION_KEY: &str = "ctrl-l";
+
+/// Key that switches to the next configuration profile and reopens the finder.
+const CYCLE_PROFILE_KEY: &str = "alt-p";
+
+/// Key that creates the session of the highlighted entry without switching to it.
+const DETACH_KEY: &str = "ctrl-o";
+
+/// Prompt of the finder when none is configured.
+const DEFAULT_PROMPT: &str = "Select project (or + to create new): ";
+
+/// Prompt of a finder that creates nothing when none is configured.
+const SELECT_ONLY_PROMPT: &str = "Select project: ";
+
+/// Line of the finder that asks for the name of a new project.
+const NEW_PROJECT_LINE: &str = "+ Create New Project...\t<NEW_PROJECT>";
+
+/// Badge of a project language: the files marking a project of the language, its Nerd Font
+/// glyph, its text fallback and its ANSI color.
+struct LanguageBadge {
+    markers: &'static [&'static str],
+    nerd: &'static str,
+    ascii: &'static str,
+    color: &'static str,
+}
+
+/// Languages recognised by their project markers, checked in order.
+const LANGUAGE_BADGES: &[LanguageBadge] = &[
+    LanguageBadge {
+        markers: &["Cargo.toml"],
+        nerd: "\u{e7a8}",
+        ascii: "rs",
+        color: "31",
+    },
+    LanguageBadge {
+        markers: &["go.mod"],
+        nerd: "\u{e627}",
+        ascii: "go",
+        color: "36",
+    },
+    LanguageBadge {
+        markers: &["tsconfig.json"],
+        nerd: "\u{e628}",
+        ascii: "ts",
+        color: "34",
+    },
+    LanguageBadge {
+        markers: &["package.json"],
+        nerd: "\u{e718}",
+        ascii: "js",
+        color: "32",
+    },
+    LanguageBadge {
+        markers: &["pyproject.toml", "setup.py", "requirements.txt"],
+        nerd: "\u{e606}",
+        ascii: "py",
+        color: "33",
+    },
+    LanguageBadge {
+        markers: &["Gemfile"],
+        nerd: "\u{e739}",
+        ascii: "rb",
+        color: "31",
+    },
+    LanguageBadge {
+        markers: &["pom.xml", "build.gradle", "build.gradle.kts"],
+        nerd: "\u{e738}",
+        ascii: "java",
+        color: "31",
+    },
+    LanguageBadge {
+        markers: &["mix.exs"],
+        nerd: "\u{e62d}",
+        ascii: "ex",
+        color: "35",
+    },
+    LanguageBadge {
+        markers: &["composer.json"],
+        nerd: "\u{e73d}",
+        ascii: "php",
+        color: "35",
+    },
+    LanguageBadge {
+        markers: &["build.zig"],
+        nerd: "\u{e6a9}",
+        ascii: "zig",
+        color: "33",
+    },
+    LanguageBadge {
+        markers: &["CMakeLists.txt", "meson.build"],
+        nerd: "\u{e61e}",
+        ascii: "c",
+        color: "34",
+    },
+    LanguageBadge {
+        markers: &["flake.nix", "default.nix"],
+        nerd: "\u{f313}",
+        ascii: "nix",
+        color: "34",
+    },
+];
+
+/// Lowest fuzzy score per query character for a direct selection to be matched fuzzily.
+///
+/// `SkimMatcherV2` scores 16 for every matched character, plus bonuses for word starts and
+/// runs and minus penalties for skipped characters. Requiring at least 16 per character
+/// makes a scattered match (e.g., from a typo) open the finder instead of a project.
+const MIN_FUZZY_SCORE_PER_CHAR: i64 = 16;
+
 /// Represents an item selected by the user, either via the fuzzy finder or direct selection.
 ///
 /// This struct holds the necessary information to proceed with creating or switching
@@ -41,6 +158,15 @@ pub struct NewProjectRequest {
     pub parent_path: PathBuf,
 }
 
+/// Represents a request to create a worktree of a Git repository for a branch.
+#[derive(Debug, Clone)]
+pub struct NewWorktreeRequest {
+    /// The path of the repository or worktree the new worktree is created from.
+    pub repository_path: PathBuf,
+    /// The branch to check out in the new worktree, created if it does not exist.
+    pub branch: String,
+}
+
 /// Represents the result of a user's selection from the fuzzy finder.
 #[derive(Debug, Clone)]
 pub enum SelectionResult {
@@ -48,6 +174,86 @@ pub enum SelectionResult {
     ExistingProject(SelectedItem),
     /// User requested to create a new project.
     NewProject(NewProjectRequest),
+    /// User requested to create a worktree of the highlighted repository.
+    NewWorktree(NewWorktreeRequest),
+    /// User requested to switch to the most recently used project session.
+    LastSession,
+    /// User requested to switch to the next configuration profile.
+    CycleProfile,
+    /// User pressed a key bound to an action on a project. `open` and `new-worktree` are
+    /// reported as `ExistingProject` and `NewWorktree` instead.
+    ProjectAction(FinderAction, SelectedItem),
+}
+
+/// Decides the badges shown before the name of each project in the finder.
+///
+/// The default shows no badges.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct Badges<'a> {
+    /// The badge style, or `FinderIcons::None` for no badges.
+    pub icons: FinderIcons,
+    /// The name and canonical working directory of every running session, to mark the
+    /// projects that have one.
+    pub running_sessions: &'a [(String, PathBuf)],
+}
+
+impl Badges<'_> {
+    /// Returns the badges of `entry` followed by a space, or an empty string without badges.
+    ///
+    /// The type badge comes first, then the language badge (blank if no language is
+    /// recognised, to keep the names aligned), and a running session is marked after both.
+    /// Every badge is colored with ANSI escape codes.
+    fn prefix(&self, entry: &DirectoryEntry) -> String {
+        let nerd = match self.icons {
+            FinderIcons::None => return String::new(),
+            FinderIcons::Ascii => false,
+            FinderIcons::Nerd => true,
+        };
+        let is_bare = entry.entry_type == DirectoryType::GitRepository
+            && !entry.resolved_path.join(".git").exists();
+        let (nerd_type, ascii_type, type_color) = match entry.entry_type {
+            DirectoryType::GitRepository if is_bare => ("\u{f401}", "[bare]", "35"),
+            DirectoryType::GitRepository => ("\u{e702}", "[repo]", "33"),
+            DirectoryType::GitWorktree { .. } => ("\u{e725}", "[wt]", "36"),
+            DirectoryType::Plain | DirectoryType::GitWorktreeContainer => {
+                ("\u{f07b}", "[dir]", "34")
+            }
+        };
+        let language = LANGUAGE_BADGES.iter().find(|language| {
+            language
+                .markers
+                .iter()
+                .any(|marker| entry.resolved_path.join(marker).exists())
+        });
+        let running = entry.has_running_session(self.running_sessions);
+
+        let colored = |text: &str, color: &str| format!("\x1b[{color}m{text}\x1b[0m");
+        if nerd {
+            format!(
+                "{} {} {}",
+                colored(nerd_type, type_color),
+                language.map_or_else(|| " ".to_string(), |l| colored(l.nerd, l.color)),
+                if running {
+                    colored("\u{f111}", "32") + " "
+                } else {
+                    String::new()
+                }
+            )
+        } else {
+            format!(
+                "{}{} {}{} {}",
+                colored(ascii_type, type_color),
+                " ".repeat(6 - ascii_type.len()),
+                language.map_or_else(String::new, |l| colored(l.ascii, l.color)),
+                " ".repeat(4 - language.map_or(0, |l| l.ascii.len())),
+                if running {
+                    colored("*", "32") + " "
+                } else {
+                    String::new()
+                }
+            )
+        }
+    }
 }
 
 /// Provides methods for interacting with the user to select a directory.
@@ -59,7 +265,8 @@ pub struct FuzzyFinder {}
 impl FuzzyFinder {
     /// Formats a `DirectoryEntry` for display in the `skim` fuzzy finder.
     ///
-    /// The output format is `display_name\tresolved_path`. The `resolved_path` is included
+    /// The output format is `display_name\tresolved_path`, with the badges of the entry (if
+    /// any) before `display_name`. The `resolved_path` is included
     /// primarily for potential use in `skim`'s preview window or if `skim` needs to parse
     /// the path itself, although the primary selection mechanism relies on parsing this
     /// line format after `skim` returns the selected line.
@@ -67,12 +274,18 @@ impl FuzzyFinder {
     /// # Arguments
     ///
     /// * `entry` - The `DirectoryEntry` to format.
+    /// * `badges` - The badges to show before the display name.
     ///
     /// # Returns
     ///
     /// A `String` formatted for `skim` input.
-    fn format_directory_entry_for_skim(entry: &DirectoryEntry) -> String {
-        format!("{}\t{}", entry.display_name, entry.resolved_path.display())
+    fn format_directory_entry_for_skim(entry: &DirectoryEntry, badges: &Badges) -> String {
+        format!(
+            "{}{}\t{}",
+            badges.prefix(entry),
+            entry.display_name,
+            entry.resolved_path.display()
+        )
     }
 
     /// Prepares the input string for the `skim` fuzzy finder by formatting each `DirectoryEntry`.
@@ -84,28 +297,241 @@ impl FuzzyFinder {
     /// # Arguments
     ///
     /// * `entries` - A slice of `DirectoryEntry` items to be presented in the fuzzy finder.
+    /// * `badges` - The badges to show before each display name.
     ///
     /// # Returns
     ///
     /// A `String` containing all formatted entries, separated by newlines.
-    pub fn prepare_skim_input(entries: &[DirectoryEntry]) -> String {
+    pub fn prepare_skim_input(entries: &[DirectoryEntry], badges: &Badges) -> String {
         entries
             .iter()
-            .map(FuzzyFinder::format_directory_entry_for_skim)
+            .map(|entry| FuzzyFinder::format_directory_entry_for_skim(entry, badges))
             .collect::<Vec<String>>()
             .join("\n")
     }
 
+    /// Prepares the input string for the tree view of the `skim` fuzzy finder.
+    ///
+    /// Entries are grouped by the search root they were found in, sorted by display name, and
+    /// the worktrees of a listed repository are indented beneath their repository. Entries found
+    /// in no search root (additional paths, or worktrees of a bare repository container) are
+    /// grouped by the directory containing them. The group is shown in a column before every
+    /// top-level line rather than on a line of its own, so every line selects a project.
+    ///
+    /// Every line keeps the `display\tresolved_path` format of the flat list, so a selection
+    /// is parsed the same way.
+    ///
+    /// # Arguments
+    ///
+    /// * `entries` - A slice of `DirectoryEntry` items to be presented in the fuzzy finder.
+    /// * `search_roots` - The search roots the entries were scanned in.
+    /// * `badges` - The badges to show before each entry name.
+    ///
+    /// # Returns
+    ///
+    /// A `String` containing the entry lines, separated by newlines.
+    pub fn prepare_tree_input(
+        entries: &[DirectoryEntry],
+        search_roots: &[PathBuf],
+        badges: &Badges,
+    ) -> String {
+        let listed: HashSet<&Path> = entries.iter().map(|e| e.resolved_path.as_path()).collect();
+        let mut worktrees_by_repo: BTreeMap<&Path, Vec<&DirectoryEntry>> = BTreeMap::new();
+        let mut groups: BTreeMap<&Path, Vec<&DirectoryEntry>> = BTreeMap::new();
+        for entry in entries {
+            match (&entry.entry_type, entry.parent_path.as_deref()) {
+                (DirectoryType::GitWorktree { .. }, Some(repo)) if listed.contains(repo) => {
+                    worktrees_by_repo.entry(repo).or_default().push(entry);
+                }
+                _ => {
+                    let group = search_roots
+                        .iter()
+                        .filter(|root| entry.path.starts_with(root))
+                        .max_by_key(|root| root.components().count())
+                        .map_or_else(
+                            || entry.path.parent().unwrap_or(Path::new("/")),
+                            PathBuf::as_path,
+                        );
+                    groups.entry(group).or_default().push(entry);
+                }
+            }
+        }
+
+        let labels: BTreeMap<&Path, String> = groups
+            .keys()
+            .map(|&group| (group, Self::display_directory(group)))
+            .collect();
+        let width = labels
+            .values()
+            .map(|label| label.chars().count())
+            .max()
+            .unwrap_or(0);
+        let mut lines = Vec::new();
+        for (group, mut group_entries) in groups {
+            group_entries.sort_by_key(|e| e.display_name.to_lowercase());
+            for entry in group_entries {
+                lines.push(format!(
+                    "{:<width$}  {}{}\t{}",
+                    labels[group],
+                    badges.prefix(entry),
+                    entry.display_name,
+                    entry.resolved_path.display()
+                ));
+                let Some(worktrees) = worktrees_by_repo.get_mut(entry.resolved_path.as_path())
+                else {
+                    continue;
+                };
+                worktrees.sort_by_key(|e| e.resolved_path.file_name().map(ToOwned::to_owned));
+                for (index, worktree) in worktrees.iter().enumerate() {
+                    let branch = if index + 1 == worktrees.len() {
+                        "└─"
+                    } else {
+                        "├─"
+                    };
+                    lines.push(format!(
+                        "{:width$}    {branch} {}{}\t{}",
+                        "",
+                        badges.prefix(worktree),
+                        worktree
+                            .resolved_path
+                            .file_name()
+                            .unwrap_or_default()
+                            .to_string_lossy(),
+                        worktree.resolved_path.display()
+                    ));
+                }
+            }
+        }
+        lines.join("\n")
+    }
+
+    /// Prepares the complete finder input: the project lines of `view`, preceded by the line
+    /// creating a new project if `offers_new_project` is set.
+    fn skim_input(
+        entries: &[DirectoryEntry],
+        search_roots: &[PathBuf],
+        badges: &Badges,
+        view: FinderView,
+        offers_new_project: bool,
+    ) -> String {
+        let projects = match view {
+            FinderView::Flat => Self::prepare_skim_input(entries, badges),
+            FinderView::Tree => Self::prepare_tree_input(entries, search_roots, badges),
+        };
+        if offers_new_project {
+            format!("{NEW_PROJECT_LINE}\n{projects}")
+        } else {
+            projects
+        }
+    }
+
+    /// Returns `finder` for a finder that must not create anything: without the bindings that
+    /// create a worktree, and without the mention of `+` in the default prompt.
+    fn without_creation(finder: &FinderConfig) -> FinderConfig {
+        let mut finder = finder.clone();
+        if let Some(bindings) = &mut finder.bindings {
+            bindings.retain(|_, action| *action != FinderAction::NewWorktree);
+        }
+        finder
+            .prompt
+            .get_or_insert_with(|| SELECT_ONLY_PROMPT.to_string());
+        finder
+    }
+
+    /// Returns `directory` for the group column of the tree view, with the home directory as `~`.
+    fn display_directory(directory: &Path) -> String {
+        dirs::home_dir()
+            .and_then(|home| directory.strip_prefix(home).ok().map(Path::to_path_buf))
+            .map_or_else(
+                || directory.display().to_string(),
+                |relative| Path::new("~").join(relative).display().to_string(),
+            )
+    }
+
+    /// Returns the finder prompt, prefixed with the active profile if there is one.
+    fn prompt(profile: Option<&str>, finder: &FinderConfig) -> String {
+        let prompt = finder.prompt.as_deref().unwrap_or(DEFAULT_PROMPT);
+        match profile {
+            Some(profile) => format!("[{profile}] {prompt}"),
+            None => prompt.to_string(),
+        }
+    }
+
+    /// Returns the skim layout: the configured one, or the one suiting `view`.
+    fn layout(finder: &FinderConfig, view: FinderView) -> &'static str {
+        // The tree reads from the top, so list it from the top as well
+        let layout = finder.layout.unwrap_or(match view {
+            FinderView::Flat => FinderLayout::Default,
+            FinderView::Tree => FinderLayout::Reverse,
+        });
+        match layout {
+            FinderLayout::Default => "default",
+            FinderLayout::Reverse => "reverse",
+        }
+    }
+
+    /// Returns the keys skim reports when they accept the selection: the built-in keys and
+    /// the keys of the configured bindings.
+    fn expected_keys(finder: &FinderConfig) -> Vec<String> {
+        let mut keys = vec![
+            LAST_SESSION_KEY.to_string(),
+            CYCLE_PROFILE_KEY.to_string(),
+            DETACH_KEY.to_string(),
+        ];
+        for key in finder.bindings.iter().flat_map(BTreeMap::keys) {
+            if !keys.contains(key) {
+                keys.push(key.clone());
+            }
+        }
+        keys
+    }
+
+    /// Returns the action `key` is bound to in the configuration, or `open-detached` for the
+    /// built-in detach key.
+    ///
+    /// A configured binding replaces the built-in action of the same key.
+    fn bound_action(finder: &FinderConfig, key: &str) -> Option<FinderAction> {
+        finder
+            .bindings
+            .as_ref()
+            .and_then(|bindings| bindings.get(key))
+            .copied()
+            .or_else(|| (key == DETACH_KEY).then_some(FinderAction::OpenDetached))
+    }
+
+    /// Returns the key from `--expect`-style bindings that accepted the selection, if any.
+    ///
+    /// Plain `Enter` accepts without a key, so `None` means the default action.
+    fn accepted_key(skim_output: &SkimOutput) -> Option<&str> {
+        match &skim_output.final_event {
+            Event::EvActAccept(Some(key)) => Some(key.as_str()),
+            _ => None,
+        }
+    }
+
     /// Runs the `skim` fuzzy finder to allow the user to select a directory entry or create a new project.
     ///
     /// Takes a slice of `DirectoryEntry` items, prepares the input for `skim`,
     /// runs the `skim` interface, and processes the user's selection.
-    /// Additionally supports creating new projects when the user types a name starting with "+".
+    /// Additionally supports creating new projects with the "+ Create New Project" line
+    /// (unless `new_project_path` is `None`),
+    /// switching to the previously used project session with `ctrl-l`, switching to the
+    /// next configuration profile with `alt-p`, creating the session of the highlighted
+    /// project without switching to it with `ctrl-o`, and running the actions of the keys
+    /// bound in `finder` on the highlighted project.
     ///
     /// # Arguments
     ///
     /// * `entries` - A slice of `DirectoryEntry` items to present to the user.
-    /// * `default_new_project_path` - The default path where new projects should be created.
+    /// * `search_roots` - The search roots the entries were scanned in, to group the tree view.
+    /// * `new_project_path` - The default path where new projects should be created, or `None`
+    ///   to offer neither new projects nor `new-worktree` bindings (e.g., for `--print`, which
+    ///   must not write to disk).
+    /// * `profile` - The active configuration profile, shown in the prompt.
+    /// * `query` - Text to pre-fill the finder query with, if any.
+    /// * `finder` - Settings of the finder, such as the view, its appearance and key bindings.
+    /// * `running_sessions` - The name and canonical working directory of every running
+    ///   session, to mark the projects that have one when badges are shown.
     ///
     /// # Returns
     ///
@@ -121,32 +547,66 @@ impl FuzzyFinder {
     /// - The selected line from `skim` cannot be parsed into the expected format.
     pub fn select_with_new_project_option(
         entries: &[DirectoryEntry],
-        default_new_project_path: &std::path::Path,
+        search_roots: &[PathBuf],
+        new_project_path: Option<&Path>,
+        profile: Option<&str>,
+        query: Option<&str>,
+        finder: &FinderConfig,
+        running_sessions: &[(String, PathBuf)],
     ) -> Result<Option<SelectionResult>> {
         if entries.is_empty() {
             debug!("No entries provided to fuzzy finder, returning None.");
             return Ok(None);
         }
 
-        // Add a special entry for creating new projects
-        let mut skim_input = "+ Create New Project...\t<NEW_PROJECT>\n".to_string();
-        skim_input.push_str(&Self::prepare_skim_input(entries));
+        let non_creating_finder;
+        let finder = if new_project_path.is_some() {
+            finder
+        } else {
+            non_creating_finder = Self::without_creation(finder);
+            &non_creating_finder
+        };
+        let view = finder.view.unwrap_or_default();
+        let badges = Badges {
+            icons: finder.icons.unwrap_or_default(),
+            running_sessions,
+        };
+        let skim_input = Self::skim_input(
+            entries,
+            search_roots,
+            &badges,
+            view,
+            new_project_path.is_some(),
+        );
 
         debug!(
-            "Skim input prepared with {} entries plus new project option.",
-            entries.len()
+            "Skim input prepared with {} entries, new project option offered: {}.",
+            entries.len(),
+            new_project_path.is_some()
         );
 
         // Configure Skim options
         let options = SkimOptionsBuilder::default()
-            .height("100%".to_string())
+            .height(finder.height.clone().unwrap_or_else(|| "100%".to_string()))
             .multi(false) // Single selection mode
-            .prompt("Select project (or + to create new): ".to_string())
+            .prompt(Self::prompt(profile, finder))
+            .query(query.map(str::to_string))
+            .layout(Self::layout(finder, view).to_string())
+            .color(finder.color.clone())
+            .header(finder.header.clone())
+            .border(finder.border.unwrap_or(false))
+            .expect(Self::expected_keys(finder))
+            // Badges are colored with ANSI escape codes
+            .ansi(badges.icons != FinderIcons::None)
             .build()
             .map_err(|e| AppError::Finder(format!("Failed to build Skim options: {e}")))?;
 
         // Create an item reader from the prepared input string
-        let item_reader = SkimItemReader::default();
+        let item_reader = SkimItemReader::new(
+            SkimItemReaderOption::default()
+                .ansi(badges.icons != FinderIcons::None)
+                .build(),
+        );
         let items = item_reader.of_bufread(Cursor::new(skim_input));
 
         // Run Skim and process the output
@@ -159,6 +619,23 @@ impl FuzzyFinder {
             return Ok(None);
         }
 
+        let accepted_key = Self::accepted_key(&skim_output);
+        let action = accepted_key.and_then(|key| Self::bound_action(finder, key));
+        match accepted_key {
+            _ if action.is_some() => {
+                debug!(key = ?accepted_key, ?action, "Bound key pressed in Skim.");
+            }
+            Some(LAST_SESSION_KEY) => {
+                debug!("Last session key pressed in Skim.");
+                return Ok(Some(SelectionResult::LastSession));
+            }
+            Some(CYCLE_PROFILE_KEY) => {
+                debug!("Cycle profile key pressed in Skim.");
+                return Ok(Some(SelectionResult::CycleProfile));
+            }
+            _ => {}
+        }
+
         let selected_items = skim_output.selected_items;
 
         if selected_items.is_empty() {
@@ -177,27 +654,36 @@ impl FuzzyFinder {
         debug!("Skim selected line: '{}'", selected_line);
 
         // Check if user wants to create a new project
-        if selected_line.starts_with("+ Create New Project") {
+        if let Some(new_project_path) = new_project_path
+            && selected_line == NEW_PROJECT_LINE
+        {
             // Prompt user for project name
-            return Self::handle_new_project_creation(default_new_project_path);
+            return Self::handle_new_project_creation(new_project_path);
         }
 
         // Parse the selected line (format: "display_name\tresolved_path")
         let parts: Vec<&str> = selected_line.splitn(2, '\t').collect();
         if parts.len() == 2 {
-            let display_name = parts[0].to_string();
             let path_str = parts[1];
             let path = PathBuf::from(path_str);
+            let entry = entries.iter().find(|entry| entry.resolved_path == path);
+            // The tree view decorates the displayed name, so take it from the entry
+            let display_name = entry.map_or_else(
+                || parts[0].trim().to_string(),
+                |entry| entry.display_name.clone(),
+            );
 
             debug!(
                 "Parsed selection - Display: '{}', Path: '{}'",
                 display_name,
                 path.display()
             );
-            Ok(Some(SelectionResult::ExistingProject(SelectedItem {
-                display_name,
-                path,
-            })))
+            let item = SelectedItem { display_name, path };
+            match action {
+                None | Some(FinderAction::Open) => Ok(Some(SelectionResult::ExistingProject(item))),
+                Some(FinderAction::NewWorktree) => Self::new_worktree_request(entries, item),
+                Some(action) => Ok(Some(SelectionResult::ProjectAction(action, item))),
+            }
         } else {
             Err(AppError::Finder(format!(
                 "Selected line from Skim has unexpected format (expected 'display\\tpath'): '{selected_line}'"
@@ -205,22 +691,29 @@ impl FuzzyFinder {
         }
     }
 
-    fn handle_new_project_creation(
-        default_new_project_path: &std::path::Path,
-    ) -> Result<Option<SelectionResult>> {
+    /// Prints `prompt` and returns the line the user enters, without surrounding whitespace.
+    ///
+    /// The prompt goes to stderr, so it stays out of the output captured with `--print`.
+    fn read_line(prompt: &str) -> Result<String> {
         use std::io::{self, Write};
 
-        print!("Enter new project name: ");
-        io::stdout()
+        eprint!("{prompt}");
+        io::stderr()
             .flush()
-            .map_err(|e| AppError::Finder(format!("Failed to flush stdout: {e}")))?;
+            .map_err(|e| AppError::Finder(format!("Failed to flush stderr: {e}")))?;
 
         let mut input = String::new();
         io::stdin()
             .read_line(&mut input)
             .map_err(|e| AppError::Finder(format!("Failed to read from stdin: {e}")))?;
+        Ok(input.trim().to_string())
+    }
 
-        let project_name = input.trim();
+    fn handle_new_project_creation(
+        default_new_project_path: &std::path::Path,
+    ) -> Result<Option<SelectionResult>> {
+        let input = Self::read_line("Enter new project name: ")?;
+        let project_name = input.as_str();
         if project_name.is_empty() {
             debug!("Empty project name provided, cancelling creation");
             return Ok(None);
@@ -240,6 +733,47 @@ impl FuzzyFinder {
         })))
     }
 
+    /// Asks for the branch of a new worktree of the selected project `item`, one of `entries`.
+    ///
+    /// Returns `Ok(None)` if no branch name is entered.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Finder` if the project is not a Git repository or worktree, or the
+    /// branch cannot be read.
+    pub fn new_worktree_request(
+        entries: &[DirectoryEntry],
+        item: SelectedItem,
+    ) -> Result<Option<SelectionResult>> {
+        let is_plain = entries.iter().any(|entry| {
+            entry.resolved_path == item.path && entry.entry_type == DirectoryType::Plain
+        });
+        if is_plain {
+            return Err(AppError::Finder(format!(
+                "Cannot create a worktree of '{}': not a Git repository",
+                item.display_name
+            )));
+        }
+        Self::handle_new_worktree_creation(item.path)
+    }
+
+    /// Asks for the branch of a new worktree of the repository at `repository_path`.
+    ///
+    /// Returns `Ok(None)` if no branch name is entered.
+    fn handle_new_worktree_creation(repository_path: PathBuf) -> Result<Option<SelectionResult>> {
+        let branch = Self::read_line("Enter branch of the new worktree: ")?;
+        if branch.is_empty() {
+            debug!("Empty branch name provided, cancelling worktree creation");
+            return Ok(None);
+        }
+
+        debug!(repository = %repository_path.display(), branch = %branch, "User requested to create a new worktree");
+        Ok(Some(SelectionResult::NewWorktree(NewWorktreeRequest {
+            repository_path,
+            branch,
+        })))
+    }
+
     /// Attempts to find a unique `DirectoryEntry` based on a user-provided search string,
     /// bypassing the interactive fuzzy finder.
     ///
@@ -477,6 +1011,66 @@ impl FuzzyFinder {
         );
         Ok(None)
     }
+
+    /// Selects the entry that best matches `query` fuzzily, for a direct selection target
+    /// that matches no entry exactly.
+    ///
+    /// Every entry is scored by its display name with `SkimMatcherV2` and smart case, the
+    /// matcher and case mode of the finder, so a direct selection ranks projects like the
+    /// finder does. Paths are not scored, since a long path contains almost any query as a
+    /// subsequence.
+    ///
+    /// # Returns
+    ///
+    /// * `Some(SelectedItem)` if a single project has the highest score, and the score
+    ///   reaches `MIN_FUZZY_SCORE_PER_CHAR` for every query character.
+    /// * `None` if no entry matches well enough, or several projects share the highest score.
+    pub fn fuzzy_select(entries: &[DirectoryEntry], query: &str) -> Option<SelectedItem> {
+        let min_score = i64::try_from(query.chars().count()).map_or(i64::MAX, |count| {
+            count.saturating_mul(MIN_FUZZY_SCORE_PER_CHAR)
+        });
+        let matcher = SkimMatcherV2::default().smart_case();
+        let mut scored: Vec<(i64, &DirectoryEntry)> = entries
+            .iter()
+            .filter_map(|entry| {
+                let score = matcher.fuzzy_match(&entry.display_name, query)?;
+                Some((score, entry))
+            })
+            .filter(|&(score, _)| score >= min_score)
+            .collect();
+        scored.sort_by(|(a, _), (b, _)| b.cmp(a));
+
+        let &(best_score, best) = scored.first()?;
+        let tied: Vec<&DirectoryEntry> = scored
+            .iter()
+            .take_while(|(score, _)| *score == best_score)
+            .map(|(_, entry)| *entry)
+            .collect();
+        if tied
+            .iter()
+            .any(|entry| entry.resolved_path != best.resolved_path)
+        {
+            debug!(
+                "Fuzzy selection: '{}' is ambiguous, {} entries score {}",
+                query,
+                tied.len(),
+                best_score
+            );
+            return None;
+        }
+
+        debug!(
+            "Fuzzy selection: Matched '{}' to entry '{}' ({}) with score {}",
+            query,
+            best.display_name,
+            best.resolved_path.display(),
+            best_score
+        );
+        Some(SelectedItem {
+            display_name: best.display_name.clone(),
+            path: best.resolved_path.clone(),
+        })
+    }
 }
 
 #[cfg(test)]
@@ -12,7 +12,7 @@ fn test_format_directory_entry_for_skim_plain() {
         parent_path: None,
     };
     assert_eq!(
-        FuzzyFinder::format_directory_entry_for_skim(&entry),
+        FuzzyFinder::format_directory_entry_for_skim(&entry, &Badges::default()),
         "project_a\t/resolved/path/to/project_a"
     );
 }
@@ -27,7 +27,7 @@ fn test_format_directory_entry_for_skim_git_repo() {
         parent_path: None,
     };
     assert_eq!(
-        FuzzyFinder::format_directory_entry_for_skim(&entry),
+        FuzzyFinder::format_directory_entry_for_skim(&entry, &Badges::default()),
         "git_repo\t/resolved/git_repo"
     );
 }
@@ -45,7 +45,7 @@ fn test_format_directory_entry_for_skim_worktree() {
         parent_path: Some(main_repo_path),
     };
     assert_eq!(
-        FuzzyFinder::format_directory_entry_for_skim(&entry),
+        FuzzyFinder::format_directory_entry_for_skim(&entry, &Badges::default()),
         "[main_repo] worktree_x\t/resolved/main_repo/worktree_x"
     );
 }
@@ -53,7 +53,10 @@ fn test_format_directory_entry_for_skim_worktree() {
 #[test]
 fn test_prepare_skim_input_empty() {
     let entries = Vec::new();
-    assert_eq!(FuzzyFinder::prepare_skim_input(&entries), "");
+    assert_eq!(
+        FuzzyFinder::prepare_skim_input(&entries, &Badges::default()),
+        ""
+    );
 }
 
 #[test]
@@ -75,7 +78,10 @@ fn test_prepare_skim_input_multiple_entries() {
         },
     ];
     let expected_output = "p1\t/res/p1\np2_display\t/res/p2";
-    assert_eq!(FuzzyFinder::prepare_skim_input(&entries), expected_output);
+    assert_eq!(
+        FuzzyFinder::prepare_skim_input(&entries, &Badges::default()),
+        expected_output
+    );
 }
 
 #[test]
@@ -83,11 +89,232 @@ fn test_select_with_empty_entries_returns_ok_none() {
     use std::path::Path;
     let entries = Vec::new();
     let default_path = Path::new("/tmp");
-    let result = FuzzyFinder::select_with_new_project_option(&entries, default_path);
+    let result = FuzzyFinder::select_with_new_project_option(
+        &entries,
+        &[],
+        Some(default_path),
+        None,
+        None,
+        &FinderConfig::default(),
+        &[],
+    );
     assert!(result.is_ok());
     assert!(result.unwrap().is_none());
 }
 
+#[test]
+fn test_skim_input_offers_new_project_only_when_creating() {
+    let entries = vec![DirectoryEntry {
+        path: PathBuf::from("/orig/p1"),
+        resolved_path: PathBuf::from("/res/p1"),
+        display_name: "p1".to_string(),
+        entry_type: DirectoryType::Plain,
+        parent_path: None,
+    }];
+
+    assert_eq!(
+        FuzzyFinder::skim_input(&entries, &[], &Badges::default(), FinderView::Flat, true),
+        format!("{NEW_PROJECT_LINE}\np1\t/res/p1")
+    );
+    assert_eq!(
+        FuzzyFinder::skim_input(&entries, &[], &Badges::default(), FinderView::Flat, false),
+        "p1\t/res/p1"
+    );
+}
+
+#[test]
+fn test_without_creation_drops_new_worktree_and_plus_prompt() {
+    let finder = FinderConfig {
+        bindings: Some(BTreeMap::from([
+            ("ctrl-w".to_string(), FinderAction::NewWorktree),
+            ("ctrl-y".to_string(), FinderAction::CopyPath),
+        ])),
+        ..FinderConfig::default()
+    };
+
+    let finder = FuzzyFinder::without_creation(&finder);
+
+    assert_eq!(
+        finder.bindings,
+        Some(BTreeMap::from([(
+            "ctrl-y".to_string(),
+            FinderAction::CopyPath
+        )]))
+    );
+    assert_eq!(FuzzyFinder::bound_action(&finder, "ctrl-w"), None);
+    assert_eq!(FuzzyFinder::prompt(None, &finder), SELECT_ONLY_PROMPT);
+}
+
+fn tree_test_entries() -> Vec<DirectoryEntry> {
+    let worktree = |path: &str, repo: &str, display_name: &str| DirectoryEntry {
+        path: PathBuf::from(path),
+        resolved_path: PathBuf::from(path),
+        display_name: display_name.to_string(),
+        entry_type: DirectoryType::GitWorktree {
+            main_worktree_path: PathBuf::from(repo),
+        },
+        parent_path: Some(PathBuf::from(repo)),
+    };
+    let plain = |path: &str, entry_type: DirectoryType| DirectoryEntry {
+        path: PathBuf::from(path),
+        resolved_path: PathBuf::from(path),
+        display_name: path.rsplit('/').next().unwrap().to_string(),
+        entry_type,
+        parent_path: None,
+    };
+    vec![
+        worktree("/wt/api-fix", "/dev/api", "[api] api-fix"),
+        plain("/work/notes", DirectoryType::Plain),
+        plain("/dev/web", DirectoryType::Plain),
+        worktree("/wt/api-feature", "/dev/api", "[api] api-feature"),
+        plain("/dev/api", DirectoryType::GitRepository),
+        plain("/dev/tools/lint", DirectoryType::Plain),
+        plain("/dev/tools/fmt", DirectoryType::Plain),
+        worktree("/bare/lib.git/main", "/bare/lib.git", "[lib.git] main"),
+    ]
+}
+
+#[test]
+fn test_prepare_tree_input_groups_and_nests_worktrees() {
+    assert_eq!(
+        FuzzyFinder::prepare_tree_input(
+            &tree_test_entries(),
+            &[PathBuf::from("/dev"), PathBuf::from("/dev/tools")],
+            &Badges::default(),
+        ),
+        [
+            "/bare/lib.git  [lib.git] main\t/bare/lib.git/main",
+            "/dev           api\t/dev/api",
+            "                 ├─ api-feature\t/wt/api-feature",
+            "                 └─ api-fix\t/wt/api-fix",
+            "/dev           web\t/dev/web",
+            "/dev/tools     fmt\t/dev/tools/fmt",
+            "/dev/tools     lint\t/dev/tools/lint",
+            "/work          notes\t/work/notes",
+        ]
+        .join("\n")
+    );
+}
+
+#[test]
+fn test_badges_show_type_language_and_running_session() {
+    let temp_dir = tempdir().unwrap();
+    let repo = temp_dir.path().join("api");
+    fs::create_dir_all(repo.join(".git")).unwrap();
+    fs::write(repo.join("Cargo.toml"), "").unwrap();
+    let bare = temp_dir.path().join("lib.git");
+    fs::create_dir(&bare).unwrap();
+    let entry = |path: &Path, entry_type: DirectoryType| DirectoryEntry {
+        path: path.to_path_buf(),
+        resolved_path: path.to_path_buf(),
+        display_name: path.file_name().unwrap().to_string_lossy().into_owned(),
+        entry_type,
+        parent_path: None,
+    };
+    let repo_entry = entry(&repo, DirectoryType::GitRepository);
+    let bare_entry = entry(&bare, DirectoryType::GitRepository);
+    let running_sessions = [("api".to_string(), PathBuf::from("/elsewhere"))];
+
+    let ascii = Badges {
+        icons: FinderIcons::Ascii,
+        running_sessions: &running_sessions,
+    };
+    assert_eq!(
+        ascii.prefix(&repo_entry),
+        "\x1b[33m[repo]\x1b[0m \x1b[31mrs\x1b[0m   \x1b[32m*\x1b[0m "
+    );
+    assert_eq!(ascii.prefix(&bare_entry), "\x1b[35m[bare]\x1b[0m      ");
+    assert_eq!(
+        FuzzyFinder::format_directory_entry_for_skim(&bare_entry, &ascii),
+        format!("\x1b[35m[bare]\x1b[0m      lib.git\t{}", bare.display())
+    );
+
+    let nerd = Badges {
+        icons: FinderIcons::Nerd,
+        running_sessions: &[],
+    };
+    assert_eq!(
+        nerd.prefix(&repo_entry),
+        "\x1b[33m\u{e702}\x1b[0m \x1b[31m\u{e7a8}\x1b[0m "
+    );
+    assert_eq!(Badges::default().prefix(&repo_entry), "");
+}
+
+#[test]
+fn test_prepare_tree_input_empty() {
+    assert_eq!(
+        FuzzyFinder::prepare_tree_input(&[], &[], &Badges::default()),
+        ""
+    );
+}
+
+#[test]
+fn test_prompt_shows_active_profile() {
+    let finder = FinderConfig::default();
+    assert_eq!(
+        FuzzyFinder::prompt(None, &finder),
+        "Select project (or + to create new): "
+    );
+    assert_eq!(
+        FuzzyFinder::prompt(Some("work"), &finder),
+        "[work] Select project (or + to create new): "
+    );
+}
+
+#[test]
+fn test_prompt_uses_configured_text() {
+    let finder = FinderConfig {
+        prompt: Some("> ".to_string()),
+        ..FinderConfig::default()
+    };
+    assert_eq!(FuzzyFinder::prompt(None, &finder), "> ");
+    assert_eq!(FuzzyFinder::prompt(Some("work"), &finder), "[work] > ");
+}
+
+#[test]
+fn test_layout_defaults_to_view_and_can_be_configured() {
+    let mut finder = FinderConfig::default();
+    assert_eq!(FuzzyFinder::layout(&finder, FinderView::Flat), "default");
+    assert_eq!(FuzzyFinder::layout(&finder, FinderView::Tree), "reverse");
+
+    finder.layout = Some(FinderLayout::Reverse);
+    assert_eq!(FuzzyFinder::layout(&finder, FinderView::Flat), "reverse");
+    finder.layout = Some(FinderLayout::Default);
+    assert_eq!(FuzzyFinder::layout(&finder, FinderView::Tree), "default");
+}
+
+#[test]
+fn test_bindings_add_expected_keys_and_replace_builtin_actions() {
+    let finder = FinderConfig {
+        bindings: Some(BTreeMap::from([
+            ("ctrl-d".to_string(), FinderAction::KillSession),
+            ("ctrl-l".to_string(), FinderAction::CopyPath),
+        ])),
+        ..FinderConfig::default()
+    };
+    assert_eq!(
+        FuzzyFinder::expected_keys(&finder),
+        vec!["ctrl-l", "alt-p", "ctrl-o", "ctrl-d"]
+    );
+    assert_eq!(
+        FuzzyFinder::bound_action(&finder, "ctrl-d"),
+        Some(FinderAction::KillSession)
+    );
+    assert_eq!(
+        FuzzyFinder::bound_action(&finder, "ctrl-l"),
+        Some(FinderAction::CopyPath)
+    );
+    assert_eq!(FuzzyFinder::bound_action(&finder, "alt-p"), None);
+    assert_eq!(
+        FuzzyFinder::bound_action(&finder, "ctrl-o"),
+        Some(FinderAction::OpenDetached)
+    );
+    assert_eq!(
+        FuzzyFinder::expected_keys(&FinderConfig::default()),
+        vec!["ctrl-l", "alt-p", "ctrl-o"]
+    );
+}
+
 // Helper to create DirectoryEntry for direct_select tests
 fn new_test_entry(p_str: &str, rp_str: &str, dn_str: &str) -> DirectoryEntry {
     DirectoryEntry {
@@ -239,6 +466,71 @@ fn test_direct_select_filename_match_ambiguous() {
     assert!(result.is_err());
 }
 
+#[test]
+fn test_fuzzy_select_picks_best_match() {
+    let entries = vec![
+        new_test_entry("/p/api-gateway", "/resolved/api-gateway", "api-gateway"),
+        new_test_entry("/p/rapid", "/resolved/rapid", "rapid"),
+        new_test_entry("/p/website", "/resolved/website", "website"),
+    ];
+    let selected = FuzzyFinder::fuzzy_select(&entries, "apigw").unwrap();
+    assert_eq!(selected.path, PathBuf::from("/resolved/api-gateway"));
+    assert!(FuzzyFinder::fuzzy_select(&entries, "xyz").is_none());
+}
+
+#[test]
+fn test_fuzzy_select_prefers_word_starts_and_runs() {
+    let entries = vec![
+        new_test_entry("/p/rapid", "/resolved/rapid", "rapid"),
+        new_test_entry("/p/api-gateway", "/resolved/api-gateway", "api-gateway"),
+    ];
+    let selected = FuzzyFinder::fuzzy_select(&entries, "api").unwrap();
+    assert_eq!(selected.path, PathBuf::from("/resolved/api-gateway"));
+}
+
+#[test]
+fn test_fuzzy_select_ignores_paths_and_scattered_matches() {
+    let entries = vec![
+        new_test_entry(
+            "/home/user/projects/website",
+            "/home/user/projects/website",
+            "website",
+        ),
+        new_test_entry(
+            "/p/some-thing-else",
+            "/p/some-thing-else",
+            "some-thing-else",
+        ),
+    ];
+    // Matches the path of the first entry, but not its display name
+    assert!(FuzzyFinder::fuzzy_select(&entries, "usrproj").is_none());
+    // Matches a display name only with long gaps, as a typo would
+    assert!(FuzzyFinder::fuzzy_select(&entries, "oile").is_none());
+    assert_eq!(
+        FuzzyFinder::fuzzy_select(&entries, "web").unwrap().path,
+        PathBuf::from("/home/user/projects/website")
+    );
+}
+
+#[test]
+fn test_fuzzy_select_ambiguous_returns_none() {
+    let entries = vec![
+        new_test_entry("/a/service", "/resolved/a/service", "service"),
+        new_test_entry("/b/service", "/resolved/b/service", "service"),
+    ];
+    assert!(FuzzyFinder::fuzzy_select(&entries, "srv").is_none());
+
+    // The same project found twice is not ambiguous
+    let duplicates = vec![
+        new_test_entry("/a/service", "/resolved/a/service", "service"),
+        new_test_entry("/link/service", "/resolved/a/service", "service"),
+    ];
+    assert_eq!(
+        FuzzyFinder::fuzzy_select(&duplicates, "srv").unwrap().path,
+        PathBuf::from("/resolved/a/service")
+    );
+}
+
 #[test]
 fn test_new_project_request_creation() {
     use crate::fuzzy_finder_interface::{NewProjectRequest, SelectionResult};
@@ -5,9 +5,10 @@
 //! - List linked Git worktrees for a given repository path.
 //! - Determine the main repository path (working directory for standard repos, path for bare repos)
 //!   from any path within the repository or its worktrees.
+//! - Create a linked worktree for a branch.
 
 use crate::error::Result;
-use git2::{Error as Git2Error, Repository};
+use git2::{BranchType, Error as Git2Error, ErrorCode, Repository, WorktreeAddOptions};
 use std::path::{Path, PathBuf};
 use tracing::{Level, debug, error, span, warn};
 
@@ -262,5 +263,96 @@ pub fn get_main_repository_path(path_in_repo: &Path) -> Result<PathBuf> {
     Ok(canonical_path)
 }
 
+/// Returns the short name of the branch checked out at `path`, if any.
+///
+/// Returns `None` if `path` is not inside a Git repository, if `HEAD` is unborn, or if
+/// `HEAD` is detached (in which case there is no branch name).
+///
+/// # Arguments
+///
+/// * `path` - A path inside the repository or worktree to inspect.
+pub fn current_branch(path: &Path) -> Option<String> {
+    let repo = Repository::open(path).ok()?;
+    let head = match repo.head() {
+        Ok(head) => head,
+        Err(e) => {
+            debug!(path = %path.display(), error = %e, "Could not resolve HEAD, no current branch");
+            return None;
+        }
+    };
+    if !head.is_branch() {
+        debug!(path = %path.display(), "HEAD is detached, no current branch");
+        return None;
+    }
+    head.shorthand().map(ToString::to_string)
+}
+
+/// Creates a linked worktree checking out `branch` and returns its canonical path.
+///
+/// The branch is created from `HEAD` of `path_in_repo` unless it exists already. The worktree
+/// is placed next to the main worktree as `<repository>-<branch>`; for a bare repository it is
+/// placed in the directory containing the repository as `<branch>`, the usual layout of a bare
+/// repository with worktrees. Slashes in the branch name become `-` in the directory name.
+///
+/// # Arguments
+///
+/// * `path_in_repo` - A path inside the repository or one of its worktrees.
+/// * `branch` - The name of the local branch to check out.
+///
+/// # Errors
+///
+/// Returns an error if `path_in_repo` is not inside a Git repository, if the worktree
+/// directory exists already, if `HEAD` cannot be resolved to create the branch, or if
+/// `git2` fails to add the worktree (e.g., the branch is checked out elsewhere).
+pub fn create_worktree(path_in_repo: &Path, branch: &str) -> Result<PathBuf> {
+    let create_span =
+        span!(Level::DEBUG, "create_worktree", path_in_repo = %path_in_repo.display(), branch);
+    let _enter = create_span.enter();
+
+    let main_path = get_main_repository_path(path_in_repo)?;
+    let main_repo = Repository::open(&main_path)?;
+    let worktree_name = branch.replace('/', "-");
+    let container = main_path
+        .parent()
+        .ok_or_else(|| Git2Error::from_str("Main repository path has no parent"))?;
+    let worktree_path = if main_repo.is_bare() {
+        container.join(&worktree_name)
+    } else {
+        let repo_name = main_path.file_name().unwrap_or_default().to_string_lossy();
+        container.join(format!("{repo_name}-{worktree_name}"))
+    };
+    if worktree_path.exists() {
+        return Err(Git2Error::from_str(&format!(
+            "Worktree directory '{}' already exists",
+            worktree_path.display()
+        ))
+        .into());
+    }
+
+    let reference = match main_repo.find_branch(branch, BranchType::Local) {
+        Ok(existing) => existing.into_reference(),
+        Err(e) if e.code() == ErrorCode::NotFound => {
+            // HEAD is resolved in the selected checkout, but the commit must belong to the
+            // repository the branch is created in.
+            let head_id = Repository::open(path_in_repo)?
+                .head()?
+                .peel_to_commit()?
+                .id();
+            let head_commit = main_repo.find_commit(head_id)?;
+            debug!(commit = %head_id, "Creating branch for the new worktree from HEAD");
+            main_repo
+                .branch(branch, &head_commit, false)?
+                .into_reference()
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let mut options = WorktreeAddOptions::new();
+    options.reference(Some(&reference));
+    main_repo.worktree(&worktree_name, &worktree_path, Some(&options))?;
+    debug!(path = %worktree_path.display(), "Created linked worktree");
+
+    Ok(std::fs::canonicalize(&worktree_path)?)
+}
+
 #[cfg(test)]
 mod tests;
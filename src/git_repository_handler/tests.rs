@@ -275,3 +275,98 @@ fn test_get_main_repository_path_for_worktree_of_bare_repo() {
         "Main repo path from worktree of bare repo should be the bare repo's path"
     );
 }
+
+#[test]
+fn test_current_branch_of_standard_repo() {
+    let dir = tempdir().unwrap();
+    let repo = init_repo(dir.path());
+
+    // No commit yet: HEAD is unborn, so there is no branch to report.
+    assert_eq!(current_branch(dir.path()), None);
+
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    let commit_oid = repo
+        .commit(
+            Some("HEAD"),
+            &signature,
+            &signature,
+            "Initial commit",
+            &tree,
+            &[],
+        )
+        .expect("Failed to create initial commit");
+    let commit = repo.find_commit(commit_oid).unwrap();
+    repo.branch("feature-x", &commit, false).unwrap();
+    repo.set_head("refs/heads/feature-x").unwrap();
+
+    assert_eq!(current_branch(dir.path()), Some("feature-x".to_string()));
+
+    repo.set_head_detached(commit_oid).unwrap();
+    assert_eq!(current_branch(dir.path()), None);
+}
+
+#[test]
+fn test_current_branch_not_a_repo() {
+    let dir = tempdir().unwrap();
+    assert_eq!(current_branch(dir.path()), None);
+}
+
+// Helper to create an initial commit on the current branch
+fn commit_initial(repo: &Repository) -> git2::Oid {
+    let signature = git2::Signature::now("Test User", "test@example.com").unwrap();
+    let tree_id = repo.index().unwrap().write_tree().unwrap();
+    let tree = repo.find_tree(tree_id).unwrap();
+    repo.commit(
+        Some("HEAD"),
+        &signature,
+        &signature,
+        "Initial commit",
+        &tree,
+        &[],
+    )
+    .expect("Failed to create initial commit")
+}
+
+#[test]
+fn test_create_worktree_with_new_branch_next_to_repo() {
+    let dir = tempdir().unwrap();
+    let repo_path = dir.path().join("app");
+    let repo = init_repo(&repo_path);
+    commit_initial(&repo);
+
+    let worktree_path = create_worktree(&repo_path, "feature/login").unwrap();
+    assert_eq!(
+        worktree_path,
+        fs::canonicalize(dir.path())
+            .unwrap()
+            .join("app-feature-login")
+    );
+    assert_eq!(
+        current_branch(&worktree_path),
+        Some("feature/login".to_string())
+    );
+    assert_eq!(list_linked_worktrees(&repo_path).unwrap().len(), 1);
+
+    // The directory is taken now, so a second worktree for the branch is refused
+    assert!(create_worktree(&repo_path, "feature/login").is_err());
+}
+
+#[test]
+fn test_create_worktree_checks_out_existing_branch() {
+    let dir = tempdir().unwrap();
+    let repo_path = dir.path().join("app");
+    let repo = init_repo(&repo_path);
+    let commit = repo.find_commit(commit_initial(&repo)).unwrap();
+    repo.branch("release", &commit, false).unwrap();
+
+    let worktree_path = create_worktree(&repo_path, "release").unwrap();
+    assert_eq!(current_branch(&worktree_path), Some("release".to_string()));
+}
+
+#[test]
+fn test_create_worktree_not_a_repo() {
+    let dir = tempdir().unwrap();
+    assert!(create_worktree(dir.path(), "feature").is_err());
+}
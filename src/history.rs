@@ -0,0 +1,140 @@
+//! Persists the history of sessionized projects.
+//!
+//! Every time a project is opened, its session name and path are recorded in a small
+//! tab-separated file in the XDG data directory. The history survives tmux server restarts
+//! and is used by the `last` subcommand (and the matching finder key) to toggle back to the
+//! previously used project.
+
+use crate::error::Result;
+use crate::session_manager::Selection;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// Name of the history file inside the application data directory.
+pub const HISTORY_FILE_NAME: &str = "history";
+
+/// Maximum number of projects kept in the history file.
+const MAX_HISTORY_ENTRIES: usize = 50;
+
+/// A single project that was opened through tmux-sessionizer.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HistoryEntry {
+    /// The session name that was used for the project.
+    pub session_name: String,
+    /// The canonical path of the project directory.
+    pub path: PathBuf,
+}
+
+/// The list of recently opened projects, most recent first.
+#[derive(Debug)]
+pub struct SessionHistory {
+    /// Path of the file the history is loaded from and saved to.
+    file: PathBuf,
+    /// Recorded entries, most recent first. Paths are unique.
+    entries: Vec<HistoryEntry>,
+}
+
+impl SessionHistory {
+    /// Loads the history from `file`.
+    ///
+    /// A missing file yields an empty history. Lines that cannot be parsed are skipped
+    /// with a warning rather than failing the whole load.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Io` if the file exists but cannot be read.
+    pub fn load(file: &Path) -> Result<Self> {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                debug!(file = %file.display(), "No history file found, starting with empty history");
+                String::new()
+            }
+            Err(e) => return Err(e.into()),
+        };
+
+        let entries = Self::parse(&content);
+        debug!(file = %file.display(), count = entries.len(), "Loaded session history");
+        Ok(Self {
+            file: file.to_path_buf(),
+            entries,
+        })
+    }
+
+    /// Parses the `session_name\tpath` lines of a history file.
+    fn parse(content: &str) -> Vec<HistoryEntry> {
+        content
+            .lines()
+            .filter(|line| !line.trim().is_empty())
+            .filter_map(|line| match line.split_once('\t') {
+                Some((session_name, path)) if !session_name.is_empty() && !path.is_empty() => {
+                    Some(HistoryEntry {
+                        session_name: session_name.to_string(),
+                        path: PathBuf::from(path),
+                    })
+                }
+                _ => {
+                    warn!(line = %line, "Skipping malformed history line");
+                    None
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the recorded entries, most recent first.
+    pub fn entries(&self) -> &[HistoryEntry] {
+        &self.entries
+    }
+
+    /// Records `selection` as the most recently used project.
+    ///
+    /// Any older entry for the same path is removed so that each project appears once.
+    pub fn record(&mut self, selection: &Selection) {
+        self.entries.retain(|entry| entry.path != selection.path);
+        self.entries.insert(
+            0,
+            HistoryEntry {
+                session_name: selection.session_name.clone(),
+                path: selection.path.clone(),
+            },
+        );
+        self.entries.truncate(MAX_HISTORY_ENTRIES);
+    }
+
+    /// Returns the most recent entry that does not refer to the current session.
+    ///
+    /// # Arguments
+    ///
+    /// * `current_session` - Name and working directory of the session the client is
+    ///   attached to, if any. Entries matching either are skipped.
+    pub fn previous(&self, current_session: Option<(&str, &Path)>) -> Option<&HistoryEntry> {
+        self.entries.iter().find(|entry| match current_session {
+            Some((name, path)) => entry.session_name != name && entry.path != path,
+            None => true,
+        })
+    }
+
+    /// Writes the history back to its file, creating the parent directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Io` if the directory cannot be created or the file cannot be written.
+    pub fn save(&self) -> Result<()> {
+        if let Some(parent) = self.file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content: String = self
+            .entries
+            .iter()
+            .map(|entry| format!("{}\t{}\n", entry.session_name, entry.path.display()))
+            .collect();
+        fs::write(&self.file, content)?;
+        debug!(file = %self.file.display(), count = self.entries.len(), "Saved session history");
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests;
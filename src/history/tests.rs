@@ -0,0 +1,114 @@
+use super::*;
+use tempfile::tempdir;
+
+fn selection(session_name: &str, path: &str) -> Selection {
+    Selection {
+        path: PathBuf::from(path),
+        display_name: session_name.to_string(),
+        session_name: session_name.to_string(),
+    }
+}
+
+#[test]
+fn test_load_missing_file_is_empty() {
+    let temp_dir = tempdir().unwrap();
+    let history = SessionHistory::load(&temp_dir.path().join(HISTORY_FILE_NAME)).unwrap();
+    assert!(history.entries().is_empty());
+}
+
+#[test]
+fn test_parse_skips_malformed_lines() {
+    let entries = SessionHistory::parse("a\t/path/a\n\nmalformed\n\t/no/name\nb\t/path/b\n");
+    assert_eq!(
+        entries,
+        vec![
+            HistoryEntry {
+                session_name: "a".to_string(),
+                path: PathBuf::from("/path/a"),
+            },
+            HistoryEntry {
+                session_name: "b".to_string(),
+                path: PathBuf::from("/path/b"),
+            },
+        ]
+    );
+}
+
+#[test]
+fn test_record_moves_existing_path_to_front() {
+    let temp_dir = tempdir().unwrap();
+    let mut history = SessionHistory::load(&temp_dir.path().join(HISTORY_FILE_NAME)).unwrap();
+
+    history.record(&selection("a", "/path/a"));
+    history.record(&selection("b", "/path/b"));
+    history.record(&selection("a", "/path/a"));
+
+    let names: Vec<&str> = history
+        .entries()
+        .iter()
+        .map(|e| e.session_name.as_str())
+        .collect();
+    assert_eq!(names, vec!["a", "b"]);
+}
+
+#[test]
+fn test_record_truncates_to_max_entries() {
+    let temp_dir = tempdir().unwrap();
+    let mut history = SessionHistory::load(&temp_dir.path().join(HISTORY_FILE_NAME)).unwrap();
+
+    for i in 0..(MAX_HISTORY_ENTRIES + 5) {
+        history.record(&selection(&format!("s{i}"), &format!("/path/{i}")));
+    }
+
+    assert_eq!(history.entries().len(), MAX_HISTORY_ENTRIES);
+    assert_eq!(
+        history.entries()[0].session_name,
+        format!("s{}", MAX_HISTORY_ENTRIES + 4)
+    );
+}
+
+#[test]
+fn test_previous_skips_current_session() {
+    let temp_dir = tempdir().unwrap();
+    let mut history = SessionHistory::load(&temp_dir.path().join(HISTORY_FILE_NAME)).unwrap();
+    history.record(&selection("b", "/path/b"));
+    history.record(&selection("a", "/path/a"));
+
+    let previous = history.previous(Some(("a", Path::new("/path/a")))).unwrap();
+    assert_eq!(previous.session_name, "b");
+
+    // A renamed current session is still recognised by its path.
+    let previous = history
+        .previous(Some(("renamed", Path::new("/path/a"))))
+        .unwrap();
+    assert_eq!(previous.session_name, "b");
+
+    // Outside tmux the most recent entry is used.
+    assert_eq!(history.previous(None).unwrap().session_name, "a");
+}
+
+#[test]
+fn test_previous_with_only_current_session_is_none() {
+    let temp_dir = tempdir().unwrap();
+    let mut history = SessionHistory::load(&temp_dir.path().join(HISTORY_FILE_NAME)).unwrap();
+    history.record(&selection("a", "/path/a"));
+
+    assert!(
+        history
+            .previous(Some(("a", Path::new("/path/a"))))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_save_and_load_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join("nested").join(HISTORY_FILE_NAME);
+    let mut history = SessionHistory::load(&file).unwrap();
+    history.record(&selection("b", "/path/b"));
+    history.record(&selection("a", "/path/a"));
+    history.save().unwrap();
+
+    let reloaded = SessionHistory::load(&file).unwrap();
+    assert_eq!(reloaded.entries(), history.entries());
+}
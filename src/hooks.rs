@@ -0,0 +1,154 @@
+//! Configurable shell hooks run around session creation and switching.
+//!
+//! Three hooks are supported (see `HooksConfig`):
+//! - `pre_create`: a shell command run outside tmux before a session is created.
+//! - `on_create`: a command typed into the first pane of a newly created session.
+//! - `on_switch`: a shell command run outside tmux before switching to an existing session.
+//!
+//! Hook commands can use the `{path}`, `{session}` and `{branch}` placeholders, which are
+//! substituted shell-quoted. Hook failures are reported as warnings and never abort session
+//! management.
+
+use crate::config::{Config, HooksConfig, PROJECT_CONFIG_FILE_NAME, load_project_config};
+use crate::git_repository_handler;
+use crate::session_manager::Selection;
+use std::borrow::Cow;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+use tracing::{debug, info, warn};
+
+/// Values substituted into hook commands.
+#[derive(Debug, Clone, PartialEq)]
+pub struct HookContext {
+    /// The project directory, substituted for `{path}`.
+    pub path: PathBuf,
+    /// The tmux session name, substituted for `{session}`.
+    pub session: String,
+    /// The current Git branch of the project, substituted for `{branch}`.
+    /// Empty if the project is not a Git repository or `HEAD` is detached.
+    pub branch: String,
+}
+
+impl HookContext {
+    /// Builds the hook context for a selection, looking up its current Git branch.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection` - The selected project.
+    /// * `session_name` - The name of the session the hook runs for. This can differ from
+    ///   the generated `selection.session_name` when an existing session was matched by path.
+    pub fn new(selection: &Selection, session_name: &str) -> Self {
+        Self {
+            path: selection.path.clone(),
+            session: session_name.to_string(),
+            branch: git_repository_handler::current_branch(&selection.path).unwrap_or_default(),
+        }
+    }
+
+    /// Replaces the `{path}`, `{session}` and `{branch}` placeholders in `template`.
+    ///
+    /// Every value is quoted for the shell, so it stays a single word even if it contains
+    /// spaces or shell syntax (e.g., a branch named `$(...)`).
+    pub fn expand(&self, template: &str) -> String {
+        let placeholders = [
+            ("{path}", self.path.to_string_lossy()),
+            ("{session}", Cow::from(self.session.as_str())),
+            ("{branch}", Cow::from(self.branch.as_str())),
+        ];
+        let mut expanded = String::with_capacity(template.len());
+        let mut rest = template;
+        // A single pass, so placeholders inside substituted values are never expanded
+        while let Some(start) = rest.find('{') {
+            expanded.push_str(&rest[..start]);
+            rest = &rest[start..];
+            match placeholders
+                .iter()
+                .find(|(placeholder, _)| rest.starts_with(placeholder))
+            {
+                Some((placeholder, value)) => {
+                    expanded.push_str(&shell_quote(value));
+                    rest = &rest[placeholder.len()..];
+                }
+                None => {
+                    expanded.push('{');
+                    rest = &rest[1..];
+                }
+            }
+        }
+        expanded.push_str(rest);
+        expanded
+    }
+}
+
+/// Quotes `value` as a single shell word; values of only safe characters are left as is.
+fn shell_quote(value: &str) -> String {
+    let is_safe = |c: char| c.is_ascii_alphanumeric() || "_-./:@%+=,".contains(c);
+    if !value.is_empty() && value.chars().all(is_safe) {
+        value.to_string()
+    } else {
+        format!("'{}'", value.replace('\'', r"'\''"))
+    }
+}
+
+/// Returns the hooks for a project: the global hooks, with any hook set in the
+/// project-local `.tmux-sessionizer.toml` taking precedence.
+pub fn resolve_hooks(config: &Config, project_path: &Path) -> HooksConfig {
+    match load_project_config(project_path) {
+        Ok(Some(project_config)) => match project_config.hooks {
+            Some(project_hooks) => config.hooks.merged_with(project_hooks),
+            None => config.hooks.clone(),
+        },
+        Ok(None) => config.hooks.clone(),
+        Err(e) => {
+            warn!(path = %project_path.display(), error = %e, "Ignoring invalid project-local configuration for hooks");
+            eprintln!("Warning: ignoring {PROJECT_CONFIG_FILE_NAME}: {e}");
+            config.hooks.clone()
+        }
+    }
+}
+
+/// Reports a failed hook on stderr and in the log.
+pub fn report_hook_failure(hook_name: &str, message: &str) {
+    warn!(hook = hook_name, error = %message, "Hook failed");
+    eprintln!("Warning: {hook_name} hook failed: {message}");
+}
+
+/// Runs a hook command with `sh -c` outside tmux, in the project directory.
+///
+/// The command is expanded with `context` first. Failures to start the shell and
+/// non-zero exit codes are reported but not returned as errors. The output of the hook goes
+/// to stderr, so it never mixes with what tmux-sessionizer prints to stdout (e.g., the
+/// session name printed with `--detach`).
+///
+/// # Arguments
+///
+/// * `hook_name` - Name of the hook, used in log and error messages.
+/// * `command` - The hook command template.
+/// * `context` - Values for the placeholders; `context.path` is also the working directory.
+pub fn run_shell_hook(hook_name: &str, command: &str, context: &HookContext) {
+    let expanded = context.expand(command);
+    info!(hook = hook_name, command = %expanded, "Running shell hook");
+    match shell_hook_command(&expanded, &context.path).status() {
+        Ok(status) if status.success() => {
+            debug!(hook = hook_name, "Shell hook completed successfully");
+        }
+        Ok(status) => report_hook_failure(hook_name, &format!("'{expanded}' exited with {status}")),
+        Err(e) => report_hook_failure(hook_name, &format!("could not run '{expanded}': {e}")),
+    }
+}
+
+/// Returns the `sh -c` command running the expanded hook `command` in `directory`, with its
+/// stdout redirected to stderr.
+fn shell_hook_command(command: &str, directory: &Path) -> Command {
+    let mut shell = Command::new("sh");
+    shell
+        .arg("-c")
+        .arg(command)
+        .current_dir(directory)
+        .stdout(Stdio::from(io::stderr()));
+    shell
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,143 @@
+use super::*;
+use std::fs;
+use tempfile::tempdir;
+
+fn context(path: &Path) -> HookContext {
+    HookContext {
+        path: path.to_path_buf(),
+        session: "my-project".to_string(),
+        branch: "main".to_string(),
+    }
+}
+
+#[test]
+fn test_expand_replaces_all_placeholders() {
+    let ctx = context(Path::new("/home/user/dev/my-project"));
+    assert_eq!(
+        ctx.expand("cd {path} && echo {session} on {branch} ({branch})"),
+        "cd /home/user/dev/my-project && echo my-project on main (main)"
+    );
+}
+
+#[test]
+fn test_expand_quotes_values_for_the_shell() {
+    let ctx = HookContext {
+        path: PathBuf::from("/home/user/my project"),
+        session: "it's".to_string(),
+        branch: "x;$(touch pwned)".to_string(),
+    };
+    assert_eq!(
+        ctx.expand("cd {path} && echo {session} {branch}"),
+        r"cd '/home/user/my project' && echo 'it'\''s' 'x;$(touch pwned)'"
+    );
+
+    let empty_branch = HookContext {
+        branch: String::new(),
+        ..ctx
+    };
+    assert_eq!(empty_branch.expand("echo {branch}"), "echo ''");
+}
+
+#[test]
+fn test_expand_does_not_expand_placeholders_in_values() {
+    let ctx = HookContext {
+        path: PathBuf::from("/tmp/{branch}"),
+        session: "{path}".to_string(),
+        branch: "$(id)".to_string(),
+    };
+    assert_eq!(
+        ctx.expand("{path} {session} {unknown"),
+        "'/tmp/{branch}' '{path}' {unknown"
+    );
+}
+
+#[test]
+fn test_expand_without_placeholders_is_unchanged() {
+    let ctx = context(Path::new("/tmp"));
+    assert_eq!(ctx.expand("git fetch --all"), "git fetch --all");
+}
+
+#[test]
+fn test_hook_context_branch_empty_outside_git() {
+    let temp_dir = tempdir().unwrap();
+    let selection = Selection {
+        path: temp_dir.path().to_path_buf(),
+        display_name: "plain".to_string(),
+        session_name: "plain".to_string(),
+    };
+
+    let ctx = HookContext::new(&selection, "renamed");
+
+    assert_eq!(ctx.session, "renamed");
+    assert_eq!(ctx.branch, "");
+}
+
+#[test]
+fn test_resolve_hooks_project_overrides_global() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(
+        temp_dir.path().join(PROJECT_CONFIG_FILE_NAME),
+        "[hooks]\non_create = \"nvim .\"\n",
+    )
+    .unwrap();
+    let config = Config {
+        hooks: HooksConfig {
+            pre_create: Some("git fetch".to_string()),
+            on_create: Some("ls".to_string()),
+            on_switch: None,
+        },
+        ..Default::default()
+    };
+
+    let hooks = resolve_hooks(&config, temp_dir.path());
+
+    assert_eq!(hooks.pre_create.as_deref(), Some("git fetch"));
+    assert_eq!(hooks.on_create.as_deref(), Some("nvim ."));
+    assert_eq!(hooks.on_switch, None);
+}
+
+#[test]
+fn test_run_shell_hook_runs_in_project_directory() {
+    let temp_dir = tempdir().unwrap();
+    let ctx = context(temp_dir.path());
+
+    run_shell_hook("pre_create", "echo {session} > hook-output.txt", &ctx);
+
+    let output = fs::read_to_string(temp_dir.path().join("hook-output.txt")).unwrap();
+    assert_eq!(output.trim(), "my-project");
+}
+
+#[test]
+fn test_run_shell_hook_does_not_run_substituted_values() {
+    let temp_dir = tempdir().unwrap();
+    let ctx = HookContext {
+        branch: "$(touch pwned); touch pwned".to_string(),
+        ..context(temp_dir.path())
+    };
+
+    run_shell_hook("pre_create", "echo {branch} > hook-output.txt", &ctx);
+
+    let output = fs::read_to_string(temp_dir.path().join("hook-output.txt")).unwrap();
+    assert_eq!(output.trim(), "$(touch pwned); touch pwned");
+    assert!(!temp_dir.path().join("pwned").exists());
+}
+
+#[test]
+fn test_shell_hook_output_goes_to_stderr() {
+    let temp_dir = tempdir().unwrap();
+
+    let output = shell_hook_command("echo hook-output && touch ran", temp_dir.path())
+        .output()
+        .unwrap();
+
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+    assert!(temp_dir.path().join("ran").exists());
+}
+
+#[test]
+fn test_run_shell_hook_failure_is_not_fatal() {
+    let temp_dir = tempdir().unwrap();
+    // Only checks that a failing hook does not panic; the failure is reported on stderr.
+    run_shell_hook("pre_create", "exit 3", &context(temp_dir.path()));
+}
@@ -0,0 +1,160 @@
+//! Generates the tmux key bindings of tmux-sessionizer and installs them in the tmux configuration.
+//!
+//! The bindings are written between two marker comments, so installing them again replaces the
+//! previous block instead of adding a second one, and the rest of the file is left untouched.
+
+use crate::error::{AppError, Result};
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, info};
+
+/// Comment that starts the block of bindings managed by tmux-sessionizer.
+pub const BEGIN_MARKER: &str = "# >>> tmux-sessionizer key bindings >>>";
+
+/// Comment that ends the block of bindings managed by tmux-sessionizer.
+pub const END_MARKER: &str = "# <<< tmux-sessionizer key bindings <<<";
+
+/// Keys bound by the snippet, each pressed after the tmux prefix.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct KeyBindings {
+    /// Key that opens the finder in a popup.
+    pub finder: String,
+    /// Key that switches to the last project.
+    pub last: String,
+    /// Key that opens the worktree creator in a popup.
+    pub worktree: String,
+}
+
+/// Returns the tmux configuration snippet binding `keys`, including the marker comments.
+pub fn snippet(keys: &KeyBindings) -> String {
+    format!(
+        "{BEGIN_MARKER}\n\
+         # Managed by `tmux-sessionizer install-keybindings`; changes inside this block are overwritten.\n\
+         bind-key {} display-popup -E -w 80% -h 80% \"tmux-sessionizer\"\n\
+         bind-key {} run-shell \"tmux-sessionizer last\"\n\
+         bind-key {} display-popup -E -w 80% -h 80% \"tmux-sessionizer worktree\"\n\
+         {END_MARKER}\n",
+        keys.finder, keys.last, keys.worktree
+    )
+}
+
+/// Returns `content` with the managed block replaced by `snippet`, or with `snippet` appended
+/// if there is no managed block yet.
+///
+/// # Errors
+///
+/// Returns `AppError::Keybindings` if only one of the markers is found, or the end marker comes
+/// first, since the extent of the managed block is then unknown.
+pub fn inject_snippet(content: &str, snippet: &str) -> Result<String> {
+    let begin = find_marker(content, BEGIN_MARKER);
+    let end = find_marker(content, END_MARKER);
+    match (begin, end) {
+        (Some(begin), Some(end)) if begin < end => {
+            let after_end = content[end..]
+                .find('\n')
+                .map_or(content.len(), |newline| end + newline + 1);
+            Ok(format!(
+                "{}{snippet}{}",
+                &content[..begin],
+                &content[after_end..]
+            ))
+        }
+        (None, None) => {
+            let mut injected = content.to_string();
+            if !injected.is_empty() {
+                if !injected.ends_with('\n') {
+                    injected.push('\n');
+                }
+                injected.push('\n');
+            }
+            injected.push_str(snippet);
+            Ok(injected)
+        }
+        _ => Err(AppError::Keybindings(format!(
+            "the markers `{BEGIN_MARKER}` and `{END_MARKER}` do not enclose a block; fix or remove them"
+        ))),
+    }
+}
+
+/// Returns the byte offset of the line consisting of `marker`, ignoring surrounding whitespace.
+fn find_marker(content: &str, marker: &str) -> Option<usize> {
+    let mut offset = 0;
+    for line in content.split_inclusive('\n') {
+        if line.trim() == marker {
+            return Some(offset);
+        }
+        offset += line.len();
+    }
+    None
+}
+
+/// Returns the tmux configuration file to install the bindings in: `~/.tmux.conf`, unless only
+/// the XDG location `$XDG_CONFIG_HOME/tmux/tmux.conf` exists.
+///
+/// # Errors
+///
+/// Returns `AppError::Keybindings` if the home directory cannot be determined.
+pub fn default_tmux_conf() -> Result<PathBuf> {
+    let home = dirs::home_dir()
+        .ok_or_else(|| AppError::Keybindings("cannot determine the home directory".to_string()))?;
+    let classic = home.join(".tmux.conf");
+    let xdg = std::env::var_os("XDG_CONFIG_HOME")
+        .filter(|dir| !dir.is_empty())
+        .map_or_else(|| home.join(".config"), PathBuf::from)
+        .join("tmux")
+        .join("tmux.conf");
+    Ok(if !classic.exists() && xdg.exists() {
+        xdg
+    } else {
+        classic
+    })
+}
+
+/// The result of installing the bindings in a tmux configuration file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum InstallOutcome {
+    /// The file already contained the same bindings.
+    Unchanged,
+    /// The file did not exist and was created (or would be, in a dry run).
+    Created,
+    /// The bindings were added to the file or replaced (or would be, in a dry run).
+    Updated,
+}
+
+/// Installs `snippet` in the tmux configuration file `path`, creating the file if needed.
+///
+/// With `dry_run`, the outcome is determined without writing anything.
+///
+/// # Errors
+///
+/// Returns an error if the file cannot be read or written, or has mismatched markers.
+pub fn install(path: &Path, snippet: &str, dry_run: bool) -> Result<InstallOutcome> {
+    let existing = match fs::read_to_string(path) {
+        Ok(content) => Some(content),
+        Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+        Err(e) => return Err(e.into()),
+    };
+    let injected = inject_snippet(existing.as_deref().unwrap_or_default(), snippet)?;
+    let outcome = match &existing {
+        Some(content) if *content == injected => return Ok(InstallOutcome::Unchanged),
+        Some(_) => InstallOutcome::Updated,
+        None => InstallOutcome::Created,
+    };
+    if dry_run {
+        debug!(path = %path.display(), ?outcome, "Dry run, not writing the tmux configuration");
+        return Ok(outcome);
+    }
+
+    if let Some(parent) = path
+        .parent()
+        .filter(|parent| !parent.as_os_str().is_empty())
+    {
+        fs::create_dir_all(parent)?;
+    }
+    fs::write(path, injected)?;
+    info!(path = %path.display(), ?outcome, "Installed tmux key bindings");
+    Ok(outcome)
+}
+
+#[cfg(test)]
+mod tests;
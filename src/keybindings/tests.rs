@@ -0,0 +1,119 @@
+use super::*;
+use tempfile::tempdir;
+
+fn default_snippet() -> String {
+    snippet(&KeyBindings {
+        finder: "f".to_string(),
+        last: "L".to_string(),
+        worktree: "W".to_string(),
+    })
+}
+
+#[test]
+fn test_snippet_binds_keys_between_markers() {
+    let snippet = snippet(&KeyBindings {
+        finder: "C-f".to_string(),
+        last: "S".to_string(),
+        worktree: "M-w".to_string(),
+    });
+
+    let lines: Vec<&str> = snippet.lines().collect();
+    assert_eq!(lines.first(), Some(&BEGIN_MARKER));
+    assert_eq!(lines.last(), Some(&END_MARKER));
+    assert!(lines.contains(&"bind-key C-f display-popup -E -w 80% -h 80% \"tmux-sessionizer\""));
+    assert!(lines.contains(&"bind-key S run-shell \"tmux-sessionizer last\""));
+    assert!(
+        lines
+            .contains(&"bind-key M-w display-popup -E -w 80% -h 80% \"tmux-sessionizer worktree\"")
+    );
+}
+
+#[test]
+fn test_inject_snippet_appends_to_existing_content() {
+    let snippet = default_snippet();
+
+    assert_eq!(inject_snippet("", &snippet).unwrap(), snippet);
+    assert_eq!(
+        inject_snippet("set -g mouse on", &snippet).unwrap(),
+        format!("set -g mouse on\n\n{snippet}")
+    );
+}
+
+#[test]
+fn test_inject_snippet_replaces_managed_block() {
+    let content = format!(
+        "set -g mouse on\n\n{BEGIN_MARKER}\nbind-key x kill-server\n{END_MARKER}\nset -g base-index 1\n"
+    );
+    let snippet = default_snippet();
+
+    let injected = inject_snippet(&content, &snippet).unwrap();
+
+    assert_eq!(
+        injected,
+        format!("set -g mouse on\n\n{snippet}set -g base-index 1\n")
+    );
+    // Injecting again changes nothing
+    assert_eq!(inject_snippet(&injected, &snippet).unwrap(), injected);
+}
+
+#[test]
+fn test_inject_snippet_rejects_unmatched_markers() {
+    let snippet = default_snippet();
+
+    for content in [
+        format!("{BEGIN_MARKER}\nbind-key x kill-server\n"),
+        format!("{END_MARKER}\n{BEGIN_MARKER}\n"),
+    ] {
+        assert!(matches!(
+            inject_snippet(&content, &snippet),
+            Err(AppError::Keybindings(_))
+        ));
+    }
+}
+
+#[test]
+fn test_install_creates_updates_and_keeps_file() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join("tmux").join("tmux.conf");
+    let snippet = default_snippet();
+
+    assert_eq!(
+        install(&path, &snippet, false).unwrap(),
+        InstallOutcome::Created
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), snippet);
+    assert_eq!(
+        install(&path, &snippet, false).unwrap(),
+        InstallOutcome::Unchanged
+    );
+
+    fs::write(&path, "set -g mouse on\n").unwrap();
+    assert_eq!(
+        install(&path, &snippet, false).unwrap(),
+        InstallOutcome::Updated
+    );
+    assert_eq!(
+        fs::read_to_string(&path).unwrap(),
+        format!("set -g mouse on\n\n{snippet}")
+    );
+}
+
+#[test]
+fn test_install_dry_run_does_not_write() {
+    let dir = tempdir().unwrap();
+    let path = dir.path().join(".tmux.conf");
+    fs::write(&path, "set -g mouse on\n").unwrap();
+
+    assert_eq!(
+        install(&path, &default_snippet(), true).unwrap(),
+        InstallOutcome::Updated
+    );
+    assert_eq!(fs::read_to_string(&path).unwrap(), "set -g mouse on\n");
+
+    let missing = dir.path().join("missing.conf");
+    assert_eq!(
+        install(&missing, &default_snippet(), true).unwrap(),
+        InstallOutcome::Created
+    );
+    assert!(!missing.exists());
+}
@@ -5,6 +5,7 @@
 //! options via a fuzzy finder (or direct selection), and creates or switches
 //! to the corresponding tmux session.
 
+mod completions;
 mod config;
 mod config_init;
 mod container_detector;
@@ -12,14 +13,23 @@ mod directory_scanner;
 mod error;
 mod fuzzy_finder_interface;
 mod git_repository_handler;
+mod history;
+mod hooks;
+mod keybindings;
 mod logging; // Ensure logging module is declared
 mod path_utils;
+mod session_environment;
+mod session_layout;
 mod session_manager;
+mod session_snapshot;
 
-use crate::config::Config;
+use crate::config::{Command, Config, FinderAction, FinderIcons, PrintFormat, ProjectType};
 use crate::directory_scanner::{DirectoryEntry, DirectoryScanner};
 use crate::error::{AppError, Result};
-use crate::fuzzy_finder_interface::{FuzzyFinder, SelectionResult};
+use crate::fuzzy_finder_interface::{FuzzyFinder, SelectedItem, SelectionResult};
+use crate::history::{HISTORY_FILE_NAME, SessionHistory};
+use crate::session_layout::SessionLayout;
+use crate::session_snapshot::{SNAPSHOT_FILE_NAME, SnapshotStore};
 
 /// Sets up the global tracing subscriber.
 ///
@@ -44,39 +54,124 @@ use crate::fuzzy_finder_interface::{FuzzyFinder, SelectionResult};
 ///
 /// * `Result<()>` - Returns `Ok(())` on successful execution, or an `AppError` if any step fails.
 fn main() -> Result<()> {
+    // Answer the shell when it completes the command line (`COMPLETE=<shell>`), before anything is printed
+    clap_complete::CompleteEnv::with_factory(config::cli_command)
+        .var(completions::COMPLETE_VAR)
+        .complete();
+
+    if Config::handle_completion_command_if_requested()? {
+        return Ok(());
+    }
+
     // 1. Check if --init flag was provided and handle initialization
     if Config::handle_init_if_requested()? {
         // Initialization was performed, exit early
         return Ok(());
     }
 
+    // Run `config show` / `config validate` before loading, so invalid configurations can be inspected
+    if Config::handle_config_command_if_requested()? {
+        return Ok(());
+    }
+
     // 2. Parse command-line arguments, load config file, and create a Config instance
-    let config = Config::new()?;
+    let mut config = Config::new()?;
     setup_logging(&config)?;
 
-    // 3. Create a DirectoryScanner instance and scan directories
-    let scanner = DirectoryScanner::new(&config);
-    tracing::info!("Starting directory scan via main...");
-    let scanned_entries = scanner.scan();
-    tracing::info!(
-        "Directory scan complete. Found {} entries.",
-        scanned_entries.len()
-    );
+    let mut layouts = load_layouts(&mut config);
 
-    // 4. Perform selection (direct or fuzzy)
-    let selection_result = handle_selection(&config, &scanned_entries)?;
+    // `worktree` runs the selection below, creating a worktree of the selected project
+    let new_worktree = config.command == Some(Command::Worktree);
+    if new_worktree && config.print.is_some() {
+        return Err(AppError::Finder(
+            "`worktree` creates a worktree and cannot be combined with --print".to_string(),
+        ));
+    }
+    if let Some(command) = config.command.as_ref().filter(|_| !new_worktree) {
+        return run_command(&config, command, &layouts);
+    }
 
-    // 5. Handle the selection outcome
-    if let Some(selection) = selection_result {
-        let sm_selection = process_selection(selection, &scanned_entries)?;
-        manage_tmux_session(&sm_selection)?;
-    } else {
-        tracing::info!("No selection made or selection cancelled.");
-        if config.direct_selection.is_some() {
-            tracing::warn!(target = %config.direct_selection.as_ref().unwrap(), "Direct selection target not found or was ambiguous.");
+    loop {
+        // 3. Create a DirectoryScanner instance and scan directories
+        let scanner = DirectoryScanner::new(&config);
+        tracing::info!("Starting directory scan via main...");
+        let mut scanned_entries = scanner.scan();
+        tracing::info!(
+            "Directory scan complete. Found {} entries.",
+            scanned_entries.len()
+        );
+        let shows_badges = config.finder.icons.unwrap_or_default() != FinderIcons::None;
+        let running_sessions = if config.entry_filter.running {
+            running_session_paths()?
+        } else if shows_badges {
+            // Badges are cosmetic, so the finder still opens without session information
+            running_session_paths().unwrap_or_else(|e| {
+                tracing::warn!(error = %e, "Failed to list running sessions for the finder badges.");
+                Vec::new()
+            })
+        } else {
+            Vec::new()
+        };
+        if !config.entry_filter.is_empty() {
+            scanned_entries = config
+                .entry_filter
+                .apply(scanned_entries, &running_sessions);
+            tracing::info!(filter = ?config.entry_filter, count = scanned_entries.len(), "Filtered scanned entries.");
+        }
+        if new_worktree {
+            // Only repositories and worktrees can get a new worktree
+            scanned_entries.retain(|entry| entry.project_type() != ProjectType::Plain);
+        }
+        if let Some(entry) = config
+            .direct_selection
+            .as_deref()
+            .and_then(|target| directory_outside_scan(&scanner, &scanned_entries, target))
+        {
+            tracing::info!(path = %entry.resolved_path.display(), "Direct selection is a directory outside the scanned projects.");
+            scanned_entries.push(entry);
+        }
+
+        // 4. Perform selection (direct or fuzzy)
+        let selection_result = match handle_selection(
+            &config,
+            &scanned_entries,
+            &scanner.search_roots(),
+            &running_sessions,
+        )? {
+            Some(SelectionResult::ExistingProject(item)) if new_worktree => {
+                FuzzyFinder::new_worktree_request(&scanned_entries, item)?
+            }
+            selection_result => selection_result,
+        };
+
+        // 5. Handle the selection outcome
+        match selection_result {
+            Some(SelectionResult::CycleProfile) => {
+                // Reload the configuration with the next profile and show the finder again
+                let next_profile = config.next_profile();
+                tracing::info!(profile = ?next_profile, "Switching configuration profile.");
+                config = Config::with_profile(next_profile)?;
+                layouts = load_layouts(&mut config);
+            }
+            Some(SelectionResult::ProjectAction(action, item)) => {
+                return run_project_action(&config, action, item, &scanned_entries, &layouts);
+            }
+            Some(selection) => {
+                return open_selection(&config, selection, &scanned_entries, &layouts);
+            }
+            None => {
+                tracing::info!("No selection made or selection cancelled.");
+                if let Some(target) = config
+                    .direct_selection
+                    .as_ref()
+                    .filter(|_| config.exact_selection)
+                {
+                    tracing::warn!(target = %target, "Direct selection target not found or was ambiguous.");
+                }
+                return Ok(());
+            }
         }
     }
-    Ok(())
 }
 
 /// Initialize global logging with the provided configuration
@@ -91,15 +186,65 @@ fn setup_logging(config: &Config) -> Result<()> {
     Ok(())
 }
 
+/// Load the tmuxinator and tmuxp layouts and add their project roots to the scanned paths
+fn load_layouts(config: &mut Config) -> Vec<SessionLayout> {
+    if !config.import_layouts {
+        tracing::debug!("Importing tmuxinator and tmuxp layouts is disabled.");
+        return Vec::new();
+    }
+    let layouts = session_layout::load_layouts();
+    for layout in &layouts {
+        if layout.root.is_dir() && !config.additional_paths.contains(&layout.root) {
+            config.additional_paths.push(layout.root.clone());
+        }
+    }
+    tracing::info!(
+        count = layouts.len(),
+        "Loaded tmuxinator and tmuxp layouts."
+    );
+    layouts
+}
+
 /// Handle user selection (either direct selection or fuzzy finder)
 fn handle_selection(
     config: &Config,
     scanned_entries: &[DirectoryEntry],
+    search_roots: &[std::path::PathBuf],
+    running_sessions: &[(String, std::path::PathBuf)],
 ) -> Result<Option<SelectionResult>> {
+    // `--print` only reports the selection, so the finder offers nothing that writes to disk
+    let new_project_path = config
+        .print
+        .is_none()
+        .then_some(config.default_new_project_path.as_path());
     if let Some(direct_selection_target) = &config.direct_selection {
         tracing::info!(target = %direct_selection_target, "Attempting direct selection.");
-        let direct_result = FuzzyFinder::direct_select(scanned_entries, direct_selection_target)?;
-        Ok(direct_result.map(SelectionResult::ExistingProject))
+        let direct_result = FuzzyFinder::direct_select(scanned_entries, direct_selection_target);
+        if config.exact_selection {
+            return Ok(direct_result?.map(SelectionResult::ExistingProject));
+        }
+        match direct_result {
+            Ok(Some(item)) => return Ok(Some(SelectionResult::ExistingProject(item))),
+            Ok(None) => {
+                if let Some(item) =
+                    FuzzyFinder::fuzzy_select(scanned_entries, direct_selection_target)
+                {
+                    return Ok(Some(SelectionResult::ExistingProject(item)));
+                }
+            }
+            Err(e) => tracing::info!(error = %e, "Direct selection target is ambiguous."),
+        }
+        // Let the user pick among the candidates instead of failing
+        tracing::info!(target = %direct_selection_target, "No unique match, launching fuzzy finder with the target as query.");
+        FuzzyFinder::select_with_new_project_option(
+            scanned_entries,
+            search_roots,
+            new_project_path,
+            config.profile.as_deref(),
+            Some(direct_selection_target),
+            &config.finder,
+            running_sessions,
+        )
     } else {
         tracing::info!("No direct selection provided, launching fuzzy finder.");
         if scanned_entries.is_empty() {
@@ -109,16 +254,365 @@ fn handle_selection(
         }
         FuzzyFinder::select_with_new_project_option(
             scanned_entries,
-            &config.default_new_project_path,
+            search_roots,
+            new_project_path,
+            config.profile.as_deref(),
+            config.finder_query.as_deref(),
+            &config.finder,
+            running_sessions,
         )
     }
 }
 
+/// List the running sessions with their canonical working directories, for `--running` and badges
+fn running_session_paths() -> Result<Vec<(String, std::path::PathBuf)>> {
+    Ok(session_manager::SessionManager::list_session_paths()?
+        .into_iter()
+        .map(|(name, path)| {
+            let path = std::fs::canonicalize(&path).unwrap_or(path);
+            (name, path)
+        })
+        .collect())
+}
+
+/// Classify a direct selection target that is a path to a directory the scan did not find.
+///
+/// Only targets written as a path (`.`, `..` or containing a `/`) are considered, so that a
+/// project name still selects the project rather than a directory of that name in the
+/// working directory.
+fn directory_outside_scan(
+    scanner: &DirectoryScanner,
+    scanned_entries: &[DirectoryEntry],
+    target: &str,
+) -> Option<DirectoryEntry> {
+    let is_path = target == "." || target == ".." || target.contains(std::path::MAIN_SEPARATOR);
+    if !is_path {
+        return None;
+    }
+    let resolved_path = std::fs::canonicalize(target).ok()?;
+    if scanned_entries
+        .iter()
+        .any(|entry| entry.resolved_path == resolved_path)
+    {
+        return None;
+    }
+    scanner.classify_path(std::path::Path::new(target))
+}
+
+/// Run a subcommand instead of the default scan-and-select flow
+fn run_command(config: &Config, command: &Command, layouts: &[SessionLayout]) -> Result<()> {
+    tracing::info!(?command, "Running subcommand.");
+    match command {
+        Command::Last => open_selection(config, SelectionResult::LastSession, &[], layouts),
+        Command::Save => save_sessions(config),
+        Command::Restore { session } => restore_sessions(config, session.as_deref()),
+        Command::InstallKeybindings {
+            file,
+            stdout,
+            dry_run,
+            finder_key,
+            last_key,
+            worktree_key,
+        } => {
+            let keys = keybindings::KeyBindings {
+                finder: finder_key.clone(),
+                last: last_key.clone(),
+                worktree: worktree_key.clone(),
+            };
+            install_keybindings(&keys, file.as_deref(), *stdout, *dry_run)
+        }
+        // Handled by the selection loop in `main`
+        Command::Worktree => Ok(()),
+        // Handled by `Config::handle_config_command_if_requested` before the configuration is loaded
+        Command::Config { .. } => Ok(()),
+        // Handled by `Config::handle_completion_command_if_requested` before the configuration is loaded
+        Command::Completions { .. } | Command::Man | Command::CompleteProjects => Ok(()),
+    }
+}
+
+/// Save a snapshot of every running session that belongs to a project from the history
+fn save_sessions(config: &Config) -> Result<()> {
+    let history = SessionHistory::load(&config.data_directory.join(HISTORY_FILE_NAME))?;
+    let snapshot_file = config.data_directory.join(SNAPSHOT_FILE_NAME);
+    let mut store = SnapshotStore::load(&snapshot_file)?;
+
+    let mut saved = 0;
+    for (session_name, session_path) in session_manager::SessionManager::list_session_paths()? {
+        let canonical_path =
+            std::fs::canonicalize(&session_path).unwrap_or_else(|_| session_path.clone());
+        let is_project = history
+            .entries()
+            .iter()
+            .any(|entry| entry.session_name == session_name || entry.path == canonical_path);
+        if !is_project {
+            tracing::debug!(session_name = %session_name, "Skipping session that was not opened through tmux-sessionizer.");
+            continue;
+        }
+        let layout = session_manager::SessionManager::capture_session_layout(
+            &session_name,
+            &canonical_path,
+        )?;
+        tracing::info!(session_name = %session_name, windows = layout.windows.len(), "Saved session snapshot.");
+        store.insert(layout);
+        saved += 1;
+    }
+
+    store.save(&snapshot_file)?;
+    println!("Saved {saved} sessions to {}", snapshot_file.display());
+    Ok(())
+}
+
+/// Recreate saved sessions that are not running, or only the one named `session`
+fn restore_sessions(config: &Config, session: Option<&str>) -> Result<()> {
+    let store = SnapshotStore::load(&config.data_directory.join(SNAPSHOT_FILE_NAME))?;
+    let layouts: Vec<&SessionLayout> =
+        match session {
+            Some(name) => vec![store.find_by_name(name).ok_or_else(|| {
+                AppError::Session(format!("No saved snapshot for session '{name}'"))
+            })?],
+            None => store.sessions.iter().collect(),
+        };
+
+    for layout in layouts {
+        if session_manager::SessionManager::session_exists(&layout.name)? {
+            tracing::info!(session_name = %layout.name, "Session is already running, not restoring.");
+            continue;
+        }
+        if !layout.root.is_dir() {
+            tracing::warn!(session_name = %layout.name, path = %layout.root.display(), "Project directory no longer exists, not restoring.");
+            eprintln!(
+                "Warning: not restoring '{}': '{}' no longer exists",
+                layout.name,
+                layout.root.display()
+            );
+            continue;
+        }
+        let environment = session_environment::resolve_session_environment(config, &layout.root);
+        session_manager::SessionManager::create_session_from_layout(
+            &layout.name,
+            layout,
+            &environment,
+        )?;
+        println!("Restored {}", layout.name);
+    }
+    Ok(())
+}
+
+/// Print the tmux key bindings, or install them in `file` (by default the tmux configuration)
+fn install_keybindings(
+    keys: &keybindings::KeyBindings,
+    file: Option<&std::path::Path>,
+    stdout: bool,
+    dry_run: bool,
+) -> Result<()> {
+    let snippet = keybindings::snippet(keys);
+    if stdout {
+        print!("{snippet}");
+        return Ok(());
+    }
+    let path = match file {
+        Some(file) => file.to_path_buf(),
+        None => keybindings::default_tmux_conf()?,
+    };
+
+    let outcome = keybindings::install(&path, &snippet, dry_run)?;
+    let target = path.display();
+    match (outcome, dry_run) {
+        (keybindings::InstallOutcome::Unchanged, _) => {
+            println!("Key bindings in {target} are already up to date");
+        }
+        (keybindings::InstallOutcome::Created, true) => {
+            println!("Would create {target} with:\n\n{snippet}");
+        }
+        (keybindings::InstallOutcome::Updated, true) => {
+            println!("Would install in {target}:\n\n{snippet}");
+        }
+        (_, false) => {
+            println!("Installed key bindings in {target}");
+            println!("Reload the tmux configuration with: tmux source-file {target}");
+        }
+    }
+    Ok(())
+}
+
+/// Resolve a selection, record it in the history and create or switch to its session
+fn open_selection(
+    config: &Config,
+    selection: SelectionResult,
+    scanned_entries: &[DirectoryEntry],
+    layouts: &[SessionLayout],
+) -> Result<()> {
+    let Some(sm_selection) = process_selection(config, selection, scanned_entries)? else {
+        return Ok(());
+    };
+    open_session(
+        config,
+        &sm_selection,
+        scanned_entries,
+        layouts,
+        config.detach,
+    )
+}
+
+/// Print the selection for `--print`, or record it in the history and create or switch to its session
+fn open_session(
+    config: &Config,
+    sm_selection: &session_manager::Selection,
+    scanned_entries: &[DirectoryEntry],
+    layouts: &[SessionLayout],
+    detach: bool,
+) -> Result<()> {
+    if let Some(format) = config.print {
+        return print_selection(config, format, sm_selection, scanned_entries);
+    }
+    record_in_history(config, sm_selection);
+    manage_tmux_session(config, sm_selection, layouts, detach)
+}
+
+/// The selection as printed by `--print=json`
+#[derive(serde_derive::Serialize)]
+struct PrintedSelection<'a> {
+    path: &'a std::path::Path,
+    session_name: &'a str,
+    #[serde(rename = "type")]
+    project_type: ProjectType,
+}
+
+/// Print the selection to stdout instead of touching tmux
+fn print_selection(
+    config: &Config,
+    format: PrintFormat,
+    sm_selection: &session_manager::Selection,
+    scanned_entries: &[DirectoryEntry],
+) -> Result<()> {
+    tracing::info!(?format, path = %sm_selection.path.display(), "Printing selection instead of opening its session.");
+    match format {
+        PrintFormat::Plain => println!("{}", sm_selection.path.display()),
+        PrintFormat::Json => {
+            // Created projects and worktrees are not among the scanned entries
+            let project_type = scanned_entries
+                .iter()
+                .find(|entry| entry.resolved_path == sm_selection.path)
+                .cloned()
+                .or_else(|| DirectoryScanner::new(config).classify_path(&sm_selection.path))
+                .as_ref()
+                .map_or(ProjectType::Plain, DirectoryEntry::project_type);
+            let printed = PrintedSelection {
+                path: &sm_selection.path,
+                session_name: &sm_selection.session_name,
+                project_type,
+            };
+            println!(
+                "{}",
+                serde_json::to_string(&printed).map_err(anyhow::Error::from)?
+            );
+        }
+    }
+    Ok(())
+}
+
+/// Run the action bound to a finder key on the highlighted project
+fn run_project_action(
+    config: &Config,
+    action: FinderAction,
+    item: SelectedItem,
+    scanned_entries: &[DirectoryEntry],
+    layouts: &[SessionLayout],
+) -> Result<()> {
+    let Some(sm_selection) = process_selection(
+        config,
+        SelectionResult::ExistingProject(item),
+        scanned_entries,
+    )?
+    else {
+        return Ok(());
+    };
+    tracing::info!(?action, path = %sm_selection.path.display(), "Running finder action.");
+    match action {
+        // The finder reports `new-worktree` as a worktree request, so both simply open the project
+        FinderAction::Open | FinderAction::NewWorktree => open_session(
+            config,
+            &sm_selection,
+            scanned_entries,
+            layouts,
+            config.detach,
+        ),
+        FinderAction::OpenDetached => {
+            open_session(config, &sm_selection, scanned_entries, layouts, true)
+        }
+        FinderAction::KillSession => {
+            match session_manager::SessionManager::find_existing_session(
+                &sm_selection,
+                config.session_match,
+            )? {
+                Some(session_name) => {
+                    session_manager::SessionManager::kill_session(&session_name)?;
+                    println!("Killed session {session_name}");
+                }
+                None => eprintln!("No running session for {}", sm_selection.path.display()),
+            }
+            Ok(())
+        }
+        FinderAction::CopyPath => {
+            let path = sm_selection.path.to_string_lossy();
+            session_manager::SessionManager::copy_to_paste_buffer(&path)?;
+            println!("Copied {path}");
+            Ok(())
+        }
+    }
+}
+
+/// Record the selection as the most recently used project. Failures are logged, not fatal.
+fn record_in_history(config: &Config, sm_selection: &session_manager::Selection) {
+    let history_file = config.data_directory.join(HISTORY_FILE_NAME);
+    let result = SessionHistory::load(&history_file).and_then(|mut history| {
+        history.record(sm_selection);
+        history.save()
+    });
+    if let Err(e) = result {
+        tracing::warn!(file = %history_file.display(), error = %e, "Failed to record selection in session history.");
+    }
+}
+
+/// Build a selection for the most recently used project other than the current session
+fn last_session_selection(config: &Config) -> Result<Option<session_manager::Selection>> {
+    let history = SessionHistory::load(&config.data_directory.join(HISTORY_FILE_NAME))?;
+    let current_session = session_manager::SessionManager::current_session()?;
+    let current_session_ref = current_session
+        .as_ref()
+        .map(|(name, path)| (name.as_str(), path.as_path()));
+
+    let Some(entry) = history.previous(current_session_ref) else {
+        tracing::info!("No previous project session found in history.");
+        eprintln!("No previous project session found in history.");
+        return Ok(None);
+    };
+
+    tracing::info!(session_name = %entry.session_name, path = %entry.path.display(), "Switching to last project session.");
+    if !entry.path.is_dir() {
+        return Err(AppError::Session(format!(
+            "Last project directory '{}' no longer exists",
+            entry.path.display()
+        )));
+    }
+    Ok(Some(session_manager::Selection {
+        path: entry.path.clone(),
+        display_name: entry
+            .path
+            .file_name()
+            .unwrap_or_default()
+            .to_string_lossy()
+            .into_owned(),
+        session_name: entry.session_name.clone(),
+    }))
+}
+
 /// Process the selection result and return session manager selection
 fn process_selection(
+    config: &Config,
     selection: SelectionResult,
     scanned_entries: &[DirectoryEntry],
-) -> Result<session_manager::Selection> {
+) -> Result<Option<session_manager::Selection>> {
     match selection {
         SelectionResult::ExistingProject(selected_item) => {
             tracing::info!("Final Selection (Existing Project):");
@@ -131,11 +625,11 @@ fn process_selection(
             });
 
             if let Some(original_dir_entry) = original_dir_entry_opt {
-                Ok(
+                Ok(Some(
                     session_manager::SessionManager::create_selection_from_directory_entry(
                         original_dir_entry,
                     ),
-                )
+                ))
             } else {
                 tracing::error!(
                     "Could not find the original directory entry for the selection. This is unexpected."
@@ -158,32 +652,140 @@ fn process_selection(
                 &new_project_request.project_name,
                 &new_project_request.parent_path,
             )
+            .map(Some)
+        }
+        SelectionResult::NewWorktree(new_worktree_request) => {
+            tracing::info!("Creating New Worktree:");
+            tracing::info!("  Branch: {}", new_worktree_request.branch);
+            tracing::info!(
+                "  Repository: {}",
+                new_worktree_request.repository_path.display()
+            );
+
+            let path = git_repository_handler::create_worktree(
+                &new_worktree_request.repository_path,
+                &new_worktree_request.branch,
+            )?;
+            let main_path = git_repository_handler::get_main_repository_path(&path)?;
+            Ok(Some(session_manager::Selection {
+                session_name: session_manager::SessionManager::generate_session_name(
+                    &path,
+                    Some(&main_path),
+                ),
+                display_name: path
+                    .file_name()
+                    .unwrap_or_default()
+                    .to_string_lossy()
+                    .into_owned(),
+                path,
+            }))
+        }
+        SelectionResult::ProjectAction(_, selected_item) => process_selection(
+            config,
+            SelectionResult::ExistingProject(selected_item),
+            scanned_entries,
+        ),
+        SelectionResult::LastSession => last_session_selection(config),
+        SelectionResult::CycleProfile => {
+            tracing::debug!("Profile cycling is handled by the selection loop.");
+            Ok(None)
         }
     }
 }
 
-/// Manage the tmux session (create or switch to existing)
-fn manage_tmux_session(sm_selection: &session_manager::Selection) -> Result<()> {
+/// Manage the tmux session (create or switch to existing).
+///
+/// With `detach`, the session is only created (starting the tmux server if needed) and its
+/// name is printed, so scripts can prepare sessions without switching to them.
+fn manage_tmux_session(
+    config: &Config,
+    sm_selection: &session_manager::Selection,
+    layouts: &[SessionLayout],
+    detach: bool,
+) -> Result<()> {
     tracing::info!("  Session Name: {}", sm_selection.session_name);
 
     match session_manager::SessionManager::is_tmux_server_running() {
-        Ok(true) => {
-            tracing::info!("Tmux server is running.");
-            match session_manager::SessionManager::session_exists(&sm_selection.session_name) {
-                Ok(true) => {
-                    tracing::info!(session_name = %sm_selection.session_name, "Session exists. Switching/Attaching.");
+        // A detached session can be created without a server, which tmux then starts
+        Ok(server_running) if server_running || detach => {
+            if server_running {
+                tracing::info!("Tmux server is running.");
+            } else {
+                tracing::info!(
+                    "Tmux server is not running, creating the detached session starts it."
+                );
+            }
+            let project_hooks = hooks::resolve_hooks(config, &sm_selection.path);
+            match session_manager::SessionManager::find_existing_session(
+                sm_selection,
+                config.session_match,
+            ) {
+                Ok(Some(existing_session_name)) if detach => {
+                    tracing::info!(session_name = %existing_session_name, "Session exists. Not switching, as requested.");
+                    println!("{existing_session_name}");
+                }
+                Ok(Some(existing_session_name)) => {
+                    tracing::info!(session_name = %existing_session_name, "Session exists. Switching/Attaching.");
+                    if let Some(on_switch) = &project_hooks.on_switch {
+                        let context = hooks::HookContext::new(sm_selection, &existing_session_name);
+                        hooks::run_shell_hook("on_switch", on_switch, &context);
+                    }
                     session_manager::SessionManager::switch_or_attach_to_session(
-                        &sm_selection.session_name,
+                        &existing_session_name,
                     )?;
-                    tracing::info!(session_name = %sm_selection.session_name, "Successfully switched/attached to session.");
+                    tracing::info!(session_name = %existing_session_name, "Successfully switched/attached to session.");
                 }
-                Ok(false) => {
+                Ok(None) => {
                     tracing::info!(session_name = %sm_selection.session_name, "Session does not exist. Creating new session.");
-                    session_manager::SessionManager::create_new_session(
-                        &sm_selection.session_name,
+                    let context = hooks::HookContext::new(sm_selection, &sm_selection.session_name);
+                    if let Some(pre_create) = &project_hooks.pre_create {
+                        hooks::run_shell_hook("pre_create", pre_create, &context);
+                    }
+                    let environment = session_environment::resolve_session_environment(
+                        config,
                         &sm_selection.path,
-                    )?;
+                    );
+                    let snapshots =
+                        SnapshotStore::load(&config.data_directory.join(SNAPSHOT_FILE_NAME))
+                            .unwrap_or_else(|e| {
+                                tracing::warn!(error = %e, "Failed to load session snapshots.");
+                                SnapshotStore::default()
+                            });
+                    if let Some(snapshot) = snapshots.find_by_path(&sm_selection.path) {
+                        tracing::info!(snapshot = %snapshot.name, "Restoring session from saved snapshot.");
+                        session_manager::SessionManager::create_session_from_layout(
+                            &sm_selection.session_name,
+                            snapshot,
+                            &environment,
+                        )?;
+                    } else if let Some(layout) =
+                        session_layout::find_layout_for_path(layouts, &sm_selection.path)
+                    {
+                        tracing::info!(layout = %layout.source.display(), "Creating session from imported layout.");
+                        session_manager::SessionManager::create_session_from_layout(
+                            &sm_selection.session_name,
+                            layout,
+                            &environment,
+                        )?;
+                    } else {
+                        // Created detached, so the on_create hook reaches the first pane
+                        // before switching or attaching, which blocks outside tmux
+                        session_manager::SessionManager::create_detached_session(
+                            &sm_selection.session_name,
+                            &sm_selection.path,
+                            &environment,
+                        )?;
+                    }
                     tracing::info!(session_name = %sm_selection.session_name, "Successfully created session.");
+                    session_manager::SessionManager::run_on_create_hook(
+                        &sm_selection.session_name,
+                        &project_hooks,
+                        &context,
+                    );
+                    if detach {
+                        println!("{}", sm_selection.session_name);
+                        return Ok(());
+                    }
 
                     tracing::info!(session_name = %sm_selection.session_name, "Attempting to switch/attach to newly created session.");
                     session_manager::SessionManager::switch_or_attach_to_session(
@@ -196,7 +798,7 @@ fn manage_tmux_session(sm_selection: &session_manager::Selection) -> Result<()>
                 }
             }
         }
-        Ok(false) => {
+        Ok(_) => {
             tracing::warn!(session_name = %sm_selection.session_name, "Tmux server is not running. Cannot manage session.");
             tracing::info!("Please start tmux server to use session management features.");
         }
@@ -1,7 +1,12 @@
 //! Utility functions for working with file paths.
 
+use crate::error::PathExpansionError;
+use glob::{MatchOptions, Pattern, PatternError};
+use nix::unistd::User;
+use std::env;
 use std::path::{Path, PathBuf};
-use tracing::{debug, trace};
+use tracing::{debug, trace, warn};
+use walkdir::WalkDir;
 
 /// Expands the tilde (`~`) character in a path to the user's home directory.
 ///
@@ -68,5 +73,246 @@ pub fn expand_tilde(path: &Path) -> Option<PathBuf> {
     }
 }
 
+/// Expands `~`, `~user` and environment variables in a configured path.
+///
+/// A leading `~` is replaced with the current user's home directory and a leading `~user`
+/// with the home directory of `user`. Anywhere in the path, `$VAR` and `${VAR}` are replaced
+/// with the value of the environment variable `VAR`, and `${VAR:-default}` with its value or,
+/// if it is unset or empty, with `default` (which may itself contain variables). A `$` that
+/// is not followed by a variable name is kept as is.
+///
+/// # Errors
+///
+/// Returns a `PathExpansionError` if a variable without a default is not set, if the home
+/// directory or `user` cannot be found, or if a `${` is not closed.
+pub fn expand_path(path: &str) -> Result<PathBuf, PathExpansionError> {
+    expand_path_with(path, |name| env::var(name).ok())
+}
+
+/// Like `expand_path`, but looks up environment variables with `lookup`.
+pub fn expand_path_with(
+    path: &str,
+    lookup: impl Fn(&str) -> Option<String>,
+) -> Result<PathBuf, PathExpansionError> {
+    trace!(input_path = %path, "Attempting path expansion");
+    let (home, rest) = match path.strip_prefix('~') {
+        Some(rest) => {
+            let (user, rest) = rest.split_at(rest.find('/').unwrap_or(rest.len()));
+            let home = if user.is_empty() {
+                dirs::home_dir().ok_or(PathExpansionError::NoHomeDirectory)?
+            } else {
+                user_home_dir(user).ok_or_else(|| PathExpansionError::UnknownUser {
+                    name: user.to_string(),
+                })?
+            };
+            (Some(home), rest.trim_start_matches('/'))
+        }
+        None => (None, path),
+    };
+
+    let expanded = expand_variables(rest, &lookup)?;
+    let expanded = match home {
+        Some(home) if expanded.is_empty() => home,
+        Some(home) => home.join(expanded),
+        None => PathBuf::from(expanded),
+    };
+    trace!(expanded_path = %expanded.display(), "Path expanded");
+    Ok(expanded)
+}
+
+/// Replaces `$VAR`, `${VAR}` and `${VAR:-default}` in `text`.
+fn expand_variables(
+    text: &str,
+    lookup: &impl Fn(&str) -> Option<String>,
+) -> Result<String, PathExpansionError> {
+    let is_name_char = |c: char| c.is_ascii_alphanumeric() || c == '_';
+    let mut expanded = String::with_capacity(text.len());
+    let mut rest = text;
+    while let Some(dollar) = rest.find('$') {
+        expanded.push_str(&rest[..dollar]);
+        let after = &rest[dollar + 1..];
+
+        if let Some(braced) = after.strip_prefix('{') {
+            let end = closing_brace(braced).ok_or(PathExpansionError::UnterminatedVariable)?;
+            let (name, default) = match braced[..end].split_once(":-") {
+                Some((name, default)) => (name, Some(default)),
+                None => (&braced[..end], None),
+            };
+            match (
+                lookup(name).filter(|value| !value.is_empty() || default.is_none()),
+                default,
+            ) {
+                (Some(value), _) => expanded.push_str(&value),
+                (None, Some(default)) => expanded.push_str(&expand_variables(default, lookup)?),
+                (None, None) => {
+                    return Err(PathExpansionError::UndefinedVariable {
+                        name: name.to_string(),
+                    });
+                }
+            }
+            rest = &braced[end + 1..];
+        } else if after.starts_with(|c: char| c.is_ascii_alphabetic() || c == '_') {
+            let end = after
+                .find(|c: char| !is_name_char(c))
+                .unwrap_or(after.len());
+            let name = &after[..end];
+            let value = lookup(name).ok_or_else(|| PathExpansionError::UndefinedVariable {
+                name: name.to_string(),
+            })?;
+            expanded.push_str(&value);
+            rest = &after[end..];
+        } else {
+            expanded.push('$');
+            rest = after;
+        }
+    }
+    expanded.push_str(rest);
+    Ok(expanded)
+}
+
+/// Returns the index of the `}` closing a `${`, skipping nested `${...}` in defaults.
+fn closing_brace(text: &str) -> Option<usize> {
+    let mut depth = 0;
+    let mut previous = '\0';
+    for (index, c) in text.char_indices() {
+        match c {
+            '{' if previous == '$' => depth += 1,
+            '}' if depth == 0 => return Some(index),
+            '}' => depth -= 1,
+            _ => {}
+        }
+        previous = c;
+    }
+    None
+}
+
+/// Returns the home directory of `user` from the system user database (`getpwnam`), which
+/// also covers users from LDAP, directory services and other NSS sources.
+fn user_home_dir(user: &str) -> Option<PathBuf> {
+    match User::from_name(user) {
+        Ok(Some(entry)) => Some(entry.dir),
+        Ok(None) => {
+            debug!(user, "User not found in the user database");
+            None
+        }
+        Err(e) => {
+            debug!(user, error = %e, "Could not look up user in the user database");
+            None
+        }
+    }
+}
+
+/// Returns `true` if `text` contains a glob wildcard (`*`, `?` or `[`).
+pub fn has_glob_wildcard(text: &str) -> bool {
+    text.contains(['*', '?', '['])
+}
+
+/// Maximum number of directories below the base of a glob that `**` descends into.
+pub const GLOBSTAR_MAX_DEPTH: usize = 8;
+
+/// A path containing glob wildcards, such as `~/work/*/` or `~/src/**/services`.
+///
+/// The pattern is compiled once and expanded to the matching directories on demand.
+/// `*`, `?` and `[...]` match within a single path component, `**` matches any number
+/// of directories, up to `GLOBSTAR_MAX_DEPTH` below the base. Wildcards do not match
+/// names starting with `.`, and hidden directories are not searched below the first
+/// wildcard. `**` does not descend into symbolic links, so link cycles cannot loop.
+#[derive(Debug, Clone)]
+pub struct PathGlob {
+    /// The directory that is searched: the components before the first wildcard.
+    base: PathBuf,
+    /// The compiled pattern matched against the directories below `base`.
+    pattern: Pattern,
+    /// Maximum depth below `base`: the number of wildcard components, or
+    /// `GLOBSTAR_MAX_DEPTH` if the pattern contains `**`.
+    max_depth: usize,
+    /// Whether the pattern contains `**`.
+    recursive: bool,
+}
+
+impl PathGlob {
+    /// Compiles `path` into a glob. Tilde expansion must already have been applied.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(PathGlob))` - If `path` contains wildcards.
+    /// * `Ok(None)` - If `path` is a literal path.
+    /// * `Err(PatternError)` - If the wildcards are invalid (e.g., `***` or an unclosed `[`).
+    pub fn new(path: &Path) -> Result<Option<Self>, PatternError> {
+        let mut base = PathBuf::new();
+        let mut wildcard_components = Vec::new();
+        for component in path.components() {
+            let text = component.as_os_str().to_string_lossy();
+            if wildcard_components.is_empty() && !has_glob_wildcard(&text) {
+                base.push(component);
+            } else {
+                wildcard_components.push(text.into_owned());
+            }
+        }
+        if wildcard_components.is_empty() {
+            return Ok(None);
+        }
+
+        let recursive = wildcard_components
+            .iter()
+            .any(|component| component == "**");
+        let max_depth = if recursive {
+            GLOBSTAR_MAX_DEPTH
+        } else {
+            wildcard_components.len()
+        };
+        let pattern = Path::new(&Pattern::escape(&base.to_string_lossy()))
+            .join(wildcard_components.join("/"));
+        let pattern = Pattern::new(&pattern.to_string_lossy())?;
+        trace!(base = %base.display(), pattern = %pattern, max_depth, recursive, "Compiled path glob");
+        Ok(Some(Self {
+            base,
+            pattern,
+            max_depth,
+            recursive,
+        }))
+    }
+
+    /// Returns the glob as written, after tilde expansion.
+    pub fn as_str(&self) -> &str {
+        self.pattern.as_str()
+    }
+
+    /// Returns the existing directories matching the glob, sorted by path.
+    pub fn expand(&self) -> Vec<PathBuf> {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            require_literal_leading_dot: true,
+            ..MatchOptions::new()
+        };
+        // Links are followed only by bounded patterns; `**` matches a linked directory but
+        // does not descend into it
+        let walker = WalkDir::new(&self.base)
+            .min_depth(1)
+            .max_depth(self.max_depth)
+            .follow_links(!self.recursive)
+            .sort_by_file_name();
+
+        let matches: Vec<PathBuf> = walker
+            .into_iter()
+            .filter_entry(|entry| entry.depth() == 0 || !entry.file_name().to_string_lossy().starts_with('.'))
+            .filter_map(|entry| match entry {
+                Ok(entry) => Some(entry),
+                Err(e) => {
+                    warn!(glob = %self.pattern, error = %e, "Error while expanding path glob, skipping entry");
+                    None
+                }
+            })
+            .filter(|entry| {
+                entry.file_type().is_dir() || (entry.path_is_symlink() && entry.path().is_dir())
+            })
+            .map(walkdir::DirEntry::into_path)
+            .filter(|path| self.pattern.matches_path_with(path, options))
+            .collect();
+        debug!(glob = %self.pattern, count = matches.len(), "Expanded path glob");
+        matches
+    }
+}
+
 #[cfg(test)]
 mod tests;
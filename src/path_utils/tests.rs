@@ -70,3 +70,160 @@ fn test_expand_tilde_no_home_dir_scenario() {
         }
     }
 }
+
+#[test]
+fn test_path_glob_literal_path_is_not_a_glob() {
+    assert!(PathGlob::new(Path::new("/srv/work")).unwrap().is_none());
+    assert!(PathGlob::new(Path::new("/srv/a[b")).is_err());
+}
+
+#[test]
+fn test_path_glob_expands_single_level_wildcards() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let base = temp_dir.path();
+    for dir in [
+        "client-a/src",
+        "client-b/src",
+        "client-b/docs",
+        ".hidden/src",
+    ] {
+        std::fs::create_dir_all(base.join(dir)).unwrap();
+    }
+    std::fs::write(base.join("client-c"), "not a directory").unwrap();
+
+    let glob = PathGlob::new(&base.join("client-*/")).unwrap().unwrap();
+    assert_eq!(
+        glob.expand(),
+        vec![base.join("client-a"), base.join("client-b")]
+    );
+
+    let glob = PathGlob::new(&base.join("*/src")).unwrap().unwrap();
+    assert_eq!(
+        glob.expand(),
+        vec![base.join("client-a/src"), base.join("client-b/src")]
+    );
+}
+
+#[test]
+fn test_path_glob_expands_recursive_wildcard() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let base = temp_dir.path();
+    for dir in [
+        "shop/services",
+        "shop/backend/services",
+        "blog/services/api",
+        ".cache/services",
+    ] {
+        std::fs::create_dir_all(base.join(dir)).unwrap();
+    }
+
+    let glob = PathGlob::new(&base.join("**/services")).unwrap().unwrap();
+    assert_eq!(
+        glob.expand(),
+        vec![
+            base.join("blog/services"),
+            base.join("shop/backend/services"),
+            base.join("shop/services"),
+        ]
+    );
+}
+
+#[cfg(unix)]
+#[test]
+fn test_path_glob_recursive_wildcard_is_bounded_and_skips_link_cycles() {
+    let temp_dir = tempfile::tempdir().unwrap();
+    let base = temp_dir.path();
+    let deep =
+        (0..GLOBSTAR_MAX_DEPTH).fold(base.to_path_buf(), |path, i| path.join(format!("d{i}")));
+    std::fs::create_dir_all(deep.join("services")).unwrap();
+    std::fs::create_dir_all(base.join("app/services")).unwrap();
+    // A link back to the base would loop forever if it were followed
+    std::os::unix::fs::symlink(base, base.join("app/loop")).unwrap();
+    std::fs::create_dir(base.join("shared")).unwrap();
+    std::os::unix::fs::symlink(base.join("app/services"), base.join("shared/services")).unwrap();
+
+    let glob = PathGlob::new(&base.join("**/services")).unwrap().unwrap();
+    assert_eq!(
+        glob.expand(),
+        vec![base.join("app/services"), base.join("shared/services")]
+    );
+}
+
+fn lookup(name: &str) -> Option<String> {
+    match name {
+        "WORKSPACE" => Some("/srv/work".to_string()),
+        "EMPTY" => Some(String::new()),
+        "SUB" => Some("api".to_string()),
+        _ => None,
+    }
+}
+
+#[test]
+fn test_expand_path_with_variables() {
+    assert_eq!(
+        expand_path_with("$WORKSPACE/projects", lookup).unwrap(),
+        PathBuf::from("/srv/work/projects")
+    );
+    assert_eq!(
+        expand_path_with("${WORKSPACE}-old/${SUB}", lookup).unwrap(),
+        PathBuf::from("/srv/work-old/api")
+    );
+    assert_eq!(
+        expand_path_with("/costs/$5/$", lookup).unwrap(),
+        PathBuf::from("/costs/$5/$")
+    );
+}
+
+#[test]
+fn test_expand_path_with_defaults() {
+    assert_eq!(
+        expand_path_with("${MISSING:-/opt/dev}/x", lookup).unwrap(),
+        PathBuf::from("/opt/dev/x")
+    );
+    assert_eq!(
+        expand_path_with("${EMPTY:-${WORKSPACE}/fallback}", lookup).unwrap(),
+        PathBuf::from("/srv/work/fallback")
+    );
+    assert_eq!(
+        expand_path_with("${WORKSPACE:-/unused}", lookup).unwrap(),
+        PathBuf::from("/srv/work")
+    );
+}
+
+#[test]
+fn test_expand_path_with_errors() {
+    assert!(matches!(
+        expand_path_with("$MISSING/src", lookup),
+        Err(PathExpansionError::UndefinedVariable { name }) if name == "MISSING"
+    ));
+    assert!(matches!(
+        expand_path_with("${MISSING}", lookup),
+        Err(PathExpansionError::UndefinedVariable { .. })
+    ));
+    assert!(matches!(
+        expand_path_with("${WORKSPACE", lookup),
+        Err(PathExpansionError::UnterminatedVariable)
+    ));
+    assert!(matches!(
+        expand_path_with("~no-such-user-here/src", lookup),
+        Err(PathExpansionError::UnknownUser { name }) if name == "no-such-user-here"
+    ));
+}
+
+#[test]
+fn test_expand_path_with_tilde() {
+    if let Some(home) = dirs::home_dir() {
+        assert_eq!(
+            expand_path_with("~/$SUB", lookup).unwrap(),
+            home.join("api")
+        );
+        assert_eq!(expand_path_with("~", lookup).unwrap(), home);
+    }
+}
+
+#[test]
+fn test_user_home_dir_looks_up_user_database() {
+    // Every Unix system has a root user with a home directory
+    assert!(user_home_dir("root").is_some_and(|home| home.is_absolute()));
+    assert_eq!(user_home_dir("no-such-user-here"), None);
+}
@@ -0,0 +1,135 @@
+//! Resolves the environment variables applied to newly created tmux sessions.
+//!
+//! Variables are collected from three sources, later sources overriding earlier ones:
+//! 1. The global `env` table of the configuration file.
+//! 2. The environment evaluated by direnv for the project, when `direnv = true` and the
+//!    project contains an `.envrc`.
+//! 3. The `env` table of the project-local `.tmux-sessionizer.toml`.
+//!
+//! Problems with any of the sources are reported as warnings and never prevent the
+//! session from being created.
+
+use crate::config::{Config, PROJECT_CONFIG_FILE_NAME, load_project_config};
+use std::collections::BTreeMap;
+use std::env;
+use std::path::Path;
+use std::process::Command;
+use tracing::{debug, warn};
+
+/// Prefix of the bookkeeping variables direnv sets, which are never exported into sessions.
+const DIRENV_INTERNAL_PREFIX: &str = "DIRENV_";
+
+/// Collects the environment variables for a new session rooted at `project_path`.
+///
+/// # Arguments
+///
+/// * `config` - The application configuration holding the global `env` table and `direnv` flag.
+/// * `project_path` - The directory the session is created for.
+///
+/// # Returns
+///
+/// The merged variables, sorted by name.
+pub fn resolve_session_environment(
+    config: &Config,
+    project_path: &Path,
+) -> BTreeMap<String, String> {
+    let mut environment = config.env.clone();
+
+    if config.direnv
+        && let Some(direnv_environment) = direnv_environment(project_path)
+    {
+        environment.extend(direnv_environment);
+    }
+
+    match load_project_config(project_path) {
+        Ok(Some(project_config)) => {
+            if let Some(project_env) = project_config.env {
+                environment.extend(project_env);
+            }
+        }
+        Ok(None) => {}
+        Err(e) => {
+            warn!(path = %project_path.display(), error = %e, "Ignoring invalid project-local configuration");
+            eprintln!("Warning: ignoring {PROJECT_CONFIG_FILE_NAME}: {e}");
+        }
+    }
+
+    debug!(path = %project_path.display(), variables = ?environment.keys().collect::<Vec<_>>(), "Resolved session environment");
+    environment
+}
+
+/// Evaluates the project's `.envrc` with direnv and returns the variables it changes.
+///
+/// Returns `None` if the project has no `.envrc`, or if direnv is not installed, the
+/// `.envrc` is not allowed, or evaluation fails (a warning is reported in these cases).
+fn direnv_environment(project_path: &Path) -> Option<BTreeMap<String, String>> {
+    if !project_path.join(".envrc").is_file() {
+        debug!(path = %project_path.display(), "No .envrc found, skipping direnv");
+        return None;
+    }
+
+    debug!(path = %project_path.display(), "Evaluating .envrc with direnv");
+    match Command::new("direnv")
+        .arg("exec")
+        .arg(project_path)
+        .args(["env", "-0"])
+        .current_dir(project_path)
+        .output()
+    {
+        Ok(output) if output.status.success() => {
+            let evaluated = parse_env_output(&output.stdout);
+            let current = env::vars_os().filter_map(|(name, value)| {
+                Some((name.into_string().ok()?, value.into_string().ok()?))
+            });
+            Some(changed_variables(evaluated, current))
+        }
+        Ok(output) => {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            warn!(path = %project_path.display(), stderr = %stderr.trim(), "direnv failed to evaluate .envrc");
+            eprintln!(
+                "Warning: direnv failed for '{}': {}",
+                project_path.display(),
+                stderr.trim()
+            );
+            None
+        }
+        Err(e) => {
+            warn!(error = %e, "Failed to run direnv");
+            eprintln!("Warning: failed to run direnv: {e}");
+            None
+        }
+    }
+}
+
+/// Parses the NUL-separated `NAME=value` records printed by `env -0`.
+fn parse_env_output(output: &[u8]) -> BTreeMap<String, String> {
+    output
+        .split(|byte| *byte == 0)
+        .filter_map(|record| {
+            let record = String::from_utf8_lossy(record);
+            let (name, value) = record.split_once('=')?;
+            if name.is_empty() {
+                return None;
+            }
+            Some((name.to_string(), value.to_string()))
+        })
+        .collect()
+}
+
+/// Returns the evaluated variables that are new or differ from the `current` environment,
+/// leaving out direnv's own bookkeeping variables.
+fn changed_variables(
+    evaluated: BTreeMap<String, String>,
+    current: impl IntoIterator<Item = (String, String)>,
+) -> BTreeMap<String, String> {
+    let current: BTreeMap<String, String> = current.into_iter().collect();
+    evaluated
+        .into_iter()
+        .filter(|(name, value)| {
+            !name.starts_with(DIRENV_INTERNAL_PREFIX) && current.get(name) != Some(value)
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests;
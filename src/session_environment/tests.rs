@@ -0,0 +1,96 @@
+use super::*;
+use std::fs;
+use tempfile::tempdir;
+
+fn config_with_env(pairs: &[(&str, &str)]) -> Config {
+    Config {
+        env: pairs
+            .iter()
+            .map(|(name, value)| ((*name).to_string(), (*value).to_string()))
+            .collect(),
+        ..Default::default()
+    }
+}
+
+#[test]
+fn test_parse_env_output() {
+    let output = b"FOO=bar\0EMPTY=\0WITH_EQUALS=a=b\0=no_name\0garbage\0";
+    let parsed = parse_env_output(output);
+
+    assert_eq!(parsed.get("FOO"), Some(&"bar".to_string()));
+    assert_eq!(parsed.get("EMPTY"), Some(&String::new()));
+    assert_eq!(parsed.get("WITH_EQUALS"), Some(&"a=b".to_string()));
+    assert_eq!(parsed.len(), 3);
+}
+
+#[test]
+fn test_changed_variables_keeps_only_new_or_modified() {
+    let evaluated: BTreeMap<String, String> = [
+        ("UNCHANGED", "1"),
+        ("MODIFIED", "new"),
+        ("ADDED", "yes"),
+        ("DIRENV_DIFF", "internal"),
+    ]
+    .into_iter()
+    .map(|(k, v)| (k.to_string(), v.to_string()))
+    .collect();
+    let current = vec![
+        ("UNCHANGED".to_string(), "1".to_string()),
+        ("MODIFIED".to_string(), "old".to_string()),
+    ];
+
+    let changed = changed_variables(evaluated, current);
+
+    assert_eq!(changed.len(), 2);
+    assert_eq!(changed.get("MODIFIED"), Some(&"new".to_string()));
+    assert_eq!(changed.get("ADDED"), Some(&"yes".to_string()));
+}
+
+#[test]
+fn test_resolve_session_environment_uses_global_env() {
+    let temp_dir = tempdir().unwrap();
+    let config = config_with_env(&[("EDITOR", "nvim")]);
+
+    let environment = resolve_session_environment(&config, temp_dir.path());
+
+    assert_eq!(environment.get("EDITOR"), Some(&"nvim".to_string()));
+}
+
+#[test]
+fn test_resolve_session_environment_project_overrides_global() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(
+        temp_dir.path().join(PROJECT_CONFIG_FILE_NAME),
+        "[env]\nEDITOR = \"hx\"\nRUST_LOG = \"debug\"\n",
+    )
+    .unwrap();
+    let config = config_with_env(&[("EDITOR", "nvim"), ("PAGER", "less")]);
+
+    let environment = resolve_session_environment(&config, temp_dir.path());
+
+    assert_eq!(environment.get("EDITOR"), Some(&"hx".to_string()));
+    assert_eq!(environment.get("RUST_LOG"), Some(&"debug".to_string()));
+    assert_eq!(environment.get("PAGER"), Some(&"less".to_string()));
+}
+
+#[test]
+fn test_resolve_session_environment_ignores_invalid_project_config() {
+    let temp_dir = tempdir().unwrap();
+    fs::write(
+        temp_dir.path().join(PROJECT_CONFIG_FILE_NAME),
+        "not valid toml [",
+    )
+    .unwrap();
+    let config = config_with_env(&[("EDITOR", "nvim")]);
+
+    let environment = resolve_session_environment(&config, temp_dir.path());
+
+    assert_eq!(environment.len(), 1);
+    assert_eq!(environment.get("EDITOR"), Some(&"nvim".to_string()));
+}
+
+#[test]
+fn test_direnv_environment_without_envrc_is_none() {
+    let temp_dir = tempdir().unwrap();
+    assert!(direnv_environment(temp_dir.path()).is_none());
+}
@@ -0,0 +1,408 @@
+//! Imports session layouts from tmuxinator and tmuxp project files.
+//!
+//! Both tools describe a session as a root directory plus a list of windows with panes and
+//! the commands started in them. This module reads those files into a common
+//! [`SessionLayout`] so that the session for a matching project can be created with the
+//! same windows and panes, and so that the layout roots can be offered in the finder even
+//! if they are outside the configured search paths.
+//!
+//! Supported files:
+//! - tmuxinator: `*.yml` / `*.yaml` in `$XDG_CONFIG_HOME/tmuxinator`, `~/.config/tmuxinator`
+//!   and `~/.tmuxinator`.
+//! - tmuxp: `*.yaml` / `*.yml` / `*.json` in `$XDG_CONFIG_HOME/tmuxp` and `~/.tmuxp`.
+//!
+//! Files that cannot be parsed, or that do not define a root directory, are skipped with a
+//! warning. ERB templates in tmuxinator files and tmuxp plugins are not evaluated.
+
+use crate::error::{AppError, Result};
+use crate::path_utils::expand_tilde;
+use serde_derive::{Deserialize, Serialize};
+use serde_norway::{Mapping, Value};
+use std::env;
+use std::fs;
+use std::path::{Path, PathBuf};
+use tracing::{debug, warn};
+
+/// The tool a layout file was written for.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LayoutFormat {
+    /// A tmuxinator project file (YAML).
+    Tmuxinator,
+    /// A tmuxp workspace file (YAML or JSON).
+    Tmuxp,
+}
+
+/// A session described by a tmuxinator or tmuxp file, or by a saved session snapshot.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct SessionLayout {
+    /// The session name defined in the file.
+    pub name: String,
+    /// The project root directory, with `~` expanded.
+    pub root: PathBuf,
+    /// The windows of the session, in order. Never empty.
+    pub windows: Vec<WindowLayout>,
+    /// The file the layout was read from. Not stored in snapshots.
+    #[serde(skip)]
+    pub source: PathBuf,
+}
+
+/// A window of a [`SessionLayout`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct WindowLayout {
+    /// The window name, if set.
+    #[serde(default)]
+    pub name: Option<String>,
+    /// The working directory of the window's panes, if different from the session root.
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+    /// A tmux layout name (e.g., `main-vertical`) or layout string applied after the panes are created.
+    #[serde(default)]
+    pub layout: Option<String>,
+    /// The panes of the window, in order. Never empty.
+    pub panes: Vec<PaneLayout>,
+}
+
+/// A pane of a [`WindowLayout`].
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct PaneLayout {
+    /// The working directory of the pane, if different from the window root.
+    #[serde(default)]
+    pub root: Option<PathBuf>,
+    /// Commands typed into the pane, in order. Empty for a plain shell.
+    #[serde(default)]
+    pub commands: Vec<String>,
+}
+
+impl SessionLayout {
+    /// Adds a plain pane to every window without panes, and a single window in the root if
+    /// there are no windows, so the layout always creates a session.
+    pub fn fill_empty(&mut self) {
+        for window in &mut self.windows {
+            if window.panes.is_empty() {
+                window.panes.push(PaneLayout::default());
+            }
+        }
+        if self.windows.is_empty() {
+            self.windows.push(WindowLayout {
+                panes: vec![PaneLayout::default()],
+                ..Default::default()
+            });
+        }
+    }
+
+    /// Returns the working directory for `window`: its own root, resolved against the
+    /// session root if relative, or the session root.
+    pub fn window_root(&self, window: &WindowLayout) -> PathBuf {
+        match &window.root {
+            Some(root) => self.root.join(root),
+            None => self.root.clone(),
+        }
+    }
+
+    /// Returns the working directory for `pane` of `window`: its own root, resolved against
+    /// the window root if relative, or the window root.
+    pub fn pane_root(&self, window: &WindowLayout, pane: &PaneLayout) -> PathBuf {
+        let window_root = self.window_root(window);
+        match &pane.root {
+            Some(root) => window_root.join(root),
+            None => window_root,
+        }
+    }
+}
+
+/// Loads all tmuxinator and tmuxp layouts from their default directories.
+pub fn load_layouts() -> Vec<SessionLayout> {
+    let config_home = env::var_os("XDG_CONFIG_HOME")
+        .map(PathBuf::from)
+        .or_else(|| dirs::home_dir().map(|home| home.join(".config")));
+    let home = dirs::home_dir();
+
+    let mut tmuxinator_dirs = Vec::new();
+    let mut tmuxp_dirs = Vec::new();
+    if let Some(config_home) = &config_home {
+        tmuxinator_dirs.push(config_home.join("tmuxinator"));
+        tmuxp_dirs.push(config_home.join("tmuxp"));
+    }
+    if let Some(home) = &home {
+        tmuxinator_dirs.push(home.join(".config").join("tmuxinator"));
+        tmuxinator_dirs.push(home.join(".tmuxinator"));
+        tmuxp_dirs.push(home.join(".tmuxp"));
+    }
+    tmuxinator_dirs.dedup();
+
+    load_layouts_from(&tmuxinator_dirs, &tmuxp_dirs)
+}
+
+/// Loads the layouts from the given tmuxinator and tmuxp directories.
+///
+/// Missing directories are ignored. Layouts are returned sorted by file within each
+/// directory; unreadable or invalid files are skipped with a warning.
+pub fn load_layouts_from(
+    tmuxinator_dirs: &[PathBuf],
+    tmuxp_dirs: &[PathBuf],
+) -> Vec<SessionLayout> {
+    let candidates = tmuxinator_dirs
+        .iter()
+        .map(|dir| (dir, LayoutFormat::Tmuxinator, &["yml", "yaml"][..]))
+        .chain(
+            tmuxp_dirs
+                .iter()
+                .map(|dir| (dir, LayoutFormat::Tmuxp, &["yaml", "yml", "json"][..])),
+        );
+
+    let mut layouts = Vec::new();
+    for (dir, format, extensions) in candidates {
+        let Ok(read_dir) = fs::read_dir(dir) else {
+            debug!(dir = %dir.display(), "Layout directory not found, skipping");
+            continue;
+        };
+        let mut files: Vec<PathBuf> = read_dir
+            .filter_map(std::result::Result::ok)
+            .map(|entry| entry.path())
+            .filter(|path| {
+                path.is_file()
+                    && path
+                        .extension()
+                        .and_then(|ext| ext.to_str())
+                        .is_some_and(|ext| extensions.contains(&ext))
+            })
+            .collect();
+        files.sort();
+
+        for file in files {
+            match load_layout_file(&file, format) {
+                Ok(layout) => {
+                    debug!(file = %file.display(), name = %layout.name, root = %layout.root.display(), "Loaded session layout");
+                    layouts.push(layout);
+                }
+                Err(e) => {
+                    warn!(file = %file.display(), error = %e, "Skipping session layout file");
+                }
+            }
+        }
+    }
+    layouts
+}
+
+/// Reads and parses a single layout file.
+///
+/// # Errors
+///
+/// Returns `AppError::Io` if the file cannot be read and `AppError::Layout` if it is not
+/// a valid layout of the given format.
+pub fn load_layout_file(file: &Path, format: LayoutFormat) -> Result<SessionLayout> {
+    let content = fs::read_to_string(file)?;
+    match format {
+        LayoutFormat::Tmuxinator => parse_tmuxinator(&content, file),
+        LayoutFormat::Tmuxp => parse_tmuxp(&content, file),
+    }
+}
+
+/// Returns the layout whose root is `path`.
+///
+/// Roots are canonicalized before comparison, so `path` should be canonical as well.
+pub fn find_layout_for_path<'a>(
+    layouts: &'a [SessionLayout],
+    path: &Path,
+) -> Option<&'a SessionLayout> {
+    layouts.iter().find(|layout| {
+        fs::canonicalize(&layout.root).unwrap_or_else(|_| layout.root.clone()) == path
+    })
+}
+
+/// Parses a tmuxinator project file.
+///
+/// Windows are single-key maps from the window name to either a command, a list of
+/// commands for a single pane, or a map with `root`, `layout` and `panes`. Panes are a
+/// command, a list of commands, or a single-key map from a pane name to commands.
+///
+/// # Errors
+///
+/// Returns `AppError::Layout` if the YAML is invalid or the file has no `root`.
+pub fn parse_tmuxinator(content: &str, source: &Path) -> Result<SessionLayout> {
+    let document = parse_document(content, source)?;
+    let root = string_field(&document, &["root", "project_root"])
+        .ok_or_else(|| layout_error(source, "missing `root`"))?;
+    let name =
+        string_field(&document, &["name", "project_name"]).unwrap_or_else(|| file_stem(source));
+
+    let windows = sequence_field(&document, "windows")
+        .iter()
+        .filter_map(|window| {
+            let (window_name, definition) = single_entry(window)?;
+            Some(tmuxinator_window(window_name, definition))
+        })
+        .collect();
+
+    build_layout(name, &root, windows, source)
+}
+
+/// Parses a tmuxp workspace file (YAML or JSON).
+///
+/// Windows are maps with `window_name`, `start_directory`, `layout`, `shell_command_before`
+/// and `panes`. Panes are a command, `null`, or a map with `shell_command` (a command or a
+/// list of commands).
+///
+/// # Errors
+///
+/// Returns `AppError::Layout` if the document is invalid or has no `start_directory`.
+pub fn parse_tmuxp(content: &str, source: &Path) -> Result<SessionLayout> {
+    let document = parse_document(content, source)?;
+    let root = string_field(&document, &["start_directory"])
+        .ok_or_else(|| layout_error(source, "missing `start_directory`"))?;
+    let name = string_field(&document, &["session_name"]).unwrap_or_else(|| file_stem(source));
+
+    let windows = sequence_field(&document, "windows")
+        .iter()
+        .map(|window| {
+            let before = commands(window.get("shell_command_before"));
+            let panes = sequence_field(window, "panes")
+                .iter()
+                .map(|pane| {
+                    let pane_commands = match pane {
+                        Value::Mapping(_) => commands(pane.get("shell_command")),
+                        other => commands(Some(other)),
+                    };
+                    PaneLayout {
+                        commands: before.iter().cloned().chain(pane_commands).collect(),
+                        ..Default::default()
+                    }
+                })
+                .collect();
+            WindowLayout {
+                name: string_field(window, &["window_name"]),
+                root: string_field(window, &["start_directory"]).map(PathBuf::from),
+                layout: string_field(window, &["layout"]),
+                panes,
+            }
+        })
+        .collect();
+
+    build_layout(name, &root, windows, source)
+}
+
+/// Converts one tmuxinator window definition.
+fn tmuxinator_window(name: &Value, definition: &Value) -> WindowLayout {
+    let name = scalar_string(name);
+    match definition {
+        Value::Mapping(_)
+            if definition.get("panes").is_some() || definition.get("layout").is_some() =>
+        {
+            let panes = sequence_field(definition, "panes")
+                .iter()
+                .map(|pane| PaneLayout {
+                    commands: match single_entry(pane) {
+                        Some((_, pane_commands)) => commands(Some(pane_commands)),
+                        None => commands(Some(pane)),
+                    },
+                    ..Default::default()
+                })
+                .collect();
+            WindowLayout {
+                name,
+                root: string_field(definition, &["root"]).map(PathBuf::from),
+                layout: string_field(definition, &["layout"]),
+                panes,
+            }
+        }
+        other => WindowLayout {
+            name,
+            panes: vec![PaneLayout {
+                commands: commands(Some(other)),
+                ..Default::default()
+            }],
+            ..Default::default()
+        },
+    }
+}
+
+/// Finishes a parsed layout: expands the root and makes sure every window has a pane
+/// and the session has a window.
+fn build_layout(
+    name: String,
+    root: &str,
+    mut windows: Vec<WindowLayout>,
+    source: &Path,
+) -> Result<SessionLayout> {
+    let root = expand_tilde(Path::new(root))
+        .ok_or_else(|| layout_error(source, &format!("cannot expand root '{root}'")))?;
+    for window in &mut windows {
+        if let Some(window_root) = &window.root {
+            window.root = expand_tilde(window_root).or_else(|| Some(window_root.clone()));
+        }
+    }
+    let mut layout = SessionLayout {
+        name,
+        root,
+        windows,
+        source: source.to_path_buf(),
+    };
+    layout.fill_empty();
+    Ok(layout)
+}
+
+/// Parses YAML (JSON is a subset of YAML) into a document root.
+fn parse_document(content: &str, source: &Path) -> Result<Value> {
+    serde_norway::from_str(content).map_err(|e| layout_error(source, &e.to_string()))
+}
+
+/// Returns the first of `keys` that holds a scalar value, as a string.
+fn string_field(value: &Value, keys: &[&str]) -> Option<String> {
+    keys.iter()
+        .find_map(|key| value.get(*key).and_then(scalar_string))
+}
+
+/// Returns the sequence stored under `key`, or an empty slice.
+fn sequence_field<'a>(value: &'a Value, key: &str) -> &'a [Value] {
+    value
+        .get(key)
+        .and_then(Value::as_sequence)
+        .map_or(&[], Vec::as_slice)
+}
+
+/// Returns the only entry of a single-key map.
+fn single_entry(value: &Value) -> Option<(&Value, &Value)> {
+    let mapping: &Mapping = value.as_mapping()?;
+    if mapping.len() == 1 {
+        mapping.iter().next()
+    } else {
+        None
+    }
+}
+
+/// Converts a string, number or boolean to a string.
+fn scalar_string(value: &Value) -> Option<String> {
+    match value {
+        Value::String(s) => Some(s.clone()),
+        Value::Number(n) => Some(n.to_string()),
+        Value::Bool(b) => Some(b.to_string()),
+        _ => None,
+    }
+}
+
+/// Converts a command, a list of commands or `null` into a list of non-empty commands.
+fn commands(value: Option<&Value>) -> Vec<String> {
+    match value {
+        Some(Value::Sequence(items)) => items.iter().filter_map(scalar_string).collect(),
+        Some(other) => scalar_string(other).into_iter().collect(),
+        None => Vec::new(),
+    }
+    .into_iter()
+    .filter(|command| !command.trim().is_empty())
+    .collect()
+}
+
+/// Returns the file name without extension, used when a layout has no name.
+fn file_stem(source: &Path) -> String {
+    source
+        .file_stem()
+        .map(|stem| stem.to_string_lossy().into_owned())
+        .unwrap_or_default()
+}
+
+fn layout_error(source: &Path, message: &str) -> AppError {
+    AppError::Layout(format!("{}: {message}", source.display()))
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,144 @@
+use super::*;
+use tempfile::tempdir;
+
+const TMUXINATOR_PROJECT: &str = r"
+name: blog
+root: /srv/blog
+windows:
+  - editor:
+      layout: main-vertical
+      panes:
+        - vim
+        - guard
+  - server: bundle exec rails s
+  - logs:
+      root: log
+      panes:
+        - tail:
+            - cd development
+            - tail -f development.log
+  - shell:
+";
+
+const TMUXP_WORKSPACE: &str = r#"{
+  "session_name": "api",
+  "start_directory": "/srv/api",
+  "windows": [
+    {
+      "window_name": "code",
+      "layout": "even-horizontal",
+      "shell_command_before": ["source .venv/bin/activate"],
+      "panes": ["nvim", {"shell_command": ["pytest -f"]}, null]
+    },
+    { "window_name": "db", "start_directory": "/srv/db" }
+  ]
+}"#;
+
+fn commands_of(window: &WindowLayout) -> Vec<Vec<&str>> {
+    window
+        .panes
+        .iter()
+        .map(|pane| pane.commands.iter().map(String::as_str).collect())
+        .collect()
+}
+
+#[test]
+fn test_parse_tmuxinator_project() {
+    let layout = parse_tmuxinator(TMUXINATOR_PROJECT, Path::new("blog.yml")).unwrap();
+
+    assert_eq!(layout.name, "blog");
+    assert_eq!(layout.root, PathBuf::from("/srv/blog"));
+    assert_eq!(layout.windows.len(), 4);
+
+    let editor = &layout.windows[0];
+    assert_eq!(editor.name.as_deref(), Some("editor"));
+    assert_eq!(editor.layout.as_deref(), Some("main-vertical"));
+    assert_eq!(commands_of(editor), vec![vec!["vim"], vec!["guard"]]);
+
+    assert_eq!(
+        commands_of(&layout.windows[1]),
+        vec![vec!["bundle exec rails s"]]
+    );
+
+    let logs = &layout.windows[2];
+    assert_eq!(layout.window_root(logs), PathBuf::from("/srv/blog/log"));
+    assert_eq!(
+        commands_of(logs),
+        vec![vec!["cd development", "tail -f development.log"]]
+    );
+
+    // A window without commands still gets a plain shell pane.
+    assert_eq!(commands_of(&layout.windows[3]), vec![Vec::<&str>::new()]);
+}
+
+#[test]
+fn test_parse_tmuxinator_requires_root() {
+    let result = parse_tmuxinator("name: no-root\nwindows:\n  - shell:\n", Path::new("x.yml"));
+    assert!(matches!(result, Err(AppError::Layout(_))));
+}
+
+#[test]
+fn test_parse_tmuxp_json_workspace() {
+    let layout = parse_tmuxp(TMUXP_WORKSPACE, Path::new("api.json")).unwrap();
+
+    assert_eq!(layout.name, "api");
+    assert_eq!(layout.root, PathBuf::from("/srv/api"));
+
+    let code = &layout.windows[0];
+    assert_eq!(code.layout.as_deref(), Some("even-horizontal"));
+    assert_eq!(
+        commands_of(code),
+        vec![
+            vec!["source .venv/bin/activate", "nvim"],
+            vec!["source .venv/bin/activate", "pytest -f"],
+            vec!["source .venv/bin/activate"],
+        ]
+    );
+
+    let db = &layout.windows[1];
+    assert_eq!(layout.window_root(db), PathBuf::from("/srv/db"));
+    assert_eq!(db.panes.len(), 1);
+}
+
+#[test]
+fn test_parse_tmuxp_name_defaults_to_file_stem() {
+    let layout = parse_tmuxp("start_directory: /srv/web\n", Path::new("/x/web.yaml")).unwrap();
+    assert_eq!(layout.name, "web");
+    assert_eq!(layout.windows.len(), 1);
+}
+
+#[test]
+fn test_load_layouts_from_skips_invalid_files() {
+    let tmuxinator_dir = tempdir().unwrap();
+    let tmuxp_dir = tempdir().unwrap();
+    fs::write(tmuxinator_dir.path().join("blog.yml"), TMUXINATOR_PROJECT).unwrap();
+    fs::write(tmuxinator_dir.path().join("broken.yml"), "name: broken\n").unwrap();
+    fs::write(tmuxinator_dir.path().join("notes.txt"), "root: /tmp\n").unwrap();
+    fs::write(tmuxp_dir.path().join("api.json"), TMUXP_WORKSPACE).unwrap();
+
+    let layouts = load_layouts_from(
+        &[tmuxinator_dir.path().to_path_buf()],
+        &[
+            tmuxp_dir.path().to_path_buf(),
+            tmuxp_dir.path().join("missing"),
+        ],
+    );
+
+    let names: Vec<&str> = layouts.iter().map(|layout| layout.name.as_str()).collect();
+    assert_eq!(names, vec!["blog", "api"]);
+}
+
+#[test]
+fn test_find_layout_for_path_canonicalizes_root() {
+    let project = tempdir().unwrap();
+    let canonical = fs::canonicalize(project.path()).unwrap();
+    let layout = parse_tmuxp(
+        &format!("start_directory: {}/.\n", project.path().display()),
+        Path::new("p.yaml"),
+    )
+    .unwrap();
+    let layouts = vec![layout];
+
+    assert!(find_layout_for_path(&layouts, &canonical).is_some());
+    assert!(find_layout_for_path(&layouts, Path::new("/nonexistent")).is_none());
+}
@@ -1,12 +1,18 @@
+use crate::config::{HooksConfig, SessionMatch};
 use crate::directory_scanner::DirectoryEntry;
 use crate::error::{AppError, Result};
+use crate::hooks::{self, HookContext};
+use crate::session_layout::SessionLayout;
+use crate::session_snapshot::{self, PANE_FORMAT, WINDOW_FORMAT};
+use std::collections::BTreeMap;
 use std::env;
 use std::ffi::OsStr;
 use std::fs;
 use std::path::{Path, PathBuf};
 use tmux_interface::{
-    AttachSession, Error as TmuxInterfaceError, HasSession, ListSessions, NewSession, SwitchClient,
-    Tmux,
+    AttachSession, DisplayMessage, Error as TmuxInterfaceError, HasSession, KillSession, ListPanes,
+    ListSessions, ListWindows, NewSession, NewWindow, RenameWindow, SelectLayout, SelectWindow,
+    SendKeys, SplitWindow, SwitchClient, Tmux, TmuxCommand,
 };
 use tracing::{debug, error, info};
 
@@ -168,6 +174,164 @@ impl SessionManager {
         }
     }
 
+    /// Lists the running tmux sessions together with their working directories.
+    ///
+    /// Uses `list-sessions -F '#{session_name}\t#{session_path}'` so that sessions
+    /// created manually or renamed after creation can still be matched to a project.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Vec<(String, PathBuf)>)` with one `(session_name, session_path)` pair per session.
+    ///   The list is empty if the tmux server is not running.
+    /// * `Err(AppError::Tmux)` if there was an issue communicating with tmux, other than the server simply not running.
+    pub fn list_session_paths() -> Result<Vec<(String, PathBuf)>> {
+        debug!("Listing tmux sessions with their working directories.");
+        let list_sessions_cmd = ListSessions::new().format("#{session_name}\t#{session_path}");
+        match Tmux::with_command(list_sessions_cmd).output() {
+            Ok(output) => {
+                let stdout = String::from_utf8_lossy(&output.stdout()).into_owned();
+                let sessions = Self::parse_session_paths(&stdout);
+                debug!(
+                    "Found {} sessions with working directories.",
+                    sessions.len()
+                );
+                Ok(sessions)
+            }
+            Err(e) => {
+                if let TmuxInterfaceError::Tmux(ref message) = e
+                    && (message.contains("no server running")
+                        || message.contains("failed to connect to server"))
+                {
+                    debug!("Tmux server not running, no sessions to list.");
+                    return Ok(Vec::new());
+                }
+                error!("Error while listing tmux sessions: {}", e);
+                Err(AppError::Tmux(e))
+            }
+        }
+    }
+
+    /// Parses the output of `list-sessions -F '#{session_name}\t#{session_path}'`.
+    ///
+    /// Lines that are empty or do not contain a tab-separated name and path are skipped.
+    fn parse_session_paths(output: &str) -> Vec<(String, PathBuf)> {
+        output
+            .lines()
+            .filter_map(|line| {
+                let (name, path) = line.split_once('\t')?;
+                if name.is_empty() || path.is_empty() {
+                    return None;
+                }
+                Some((name.to_string(), PathBuf::from(path)))
+            })
+            .collect()
+    }
+
+    /// Returns the name of the first session whose working directory is `path`.
+    ///
+    /// Session paths are canonicalized before comparison so that symlinked or
+    /// non-normalized paths reported by tmux still match the canonical selection path.
+    fn match_session_by_path(path: &Path, sessions: &[(String, PathBuf)]) -> Option<String> {
+        sessions
+            .iter()
+            .find(|(_, session_path)| {
+                fs::canonicalize(session_path).unwrap_or_else(|_| session_path.clone()) == path
+            })
+            .map(|(name, _)| name.clone())
+    }
+
+    /// Finds a running session whose working directory matches `path`.
+    ///
+    /// # Arguments
+    ///
+    /// * `path`: The canonical path of the selected directory.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(name))` with the name of the matching session.
+    /// * `Ok(None)` if no session has `path` as its working directory.
+    /// * `Err(AppError::Tmux)` if listing the sessions failed.
+    pub fn find_session_by_path(path: &Path) -> Result<Option<String>> {
+        let sessions = Self::list_session_paths()?;
+        let found = Self::match_session_by_path(path, &sessions);
+        debug!(
+            "Session lookup by path '{}' found: {:?}",
+            path.display(),
+            found
+        );
+        Ok(found)
+    }
+
+    /// Finds the running session for a selection, if there is one.
+    ///
+    /// Both the generated session name (exact `=name` match) and the session working
+    /// directory are checked; `strategy` decides which lookup is tried first.
+    ///
+    /// # Arguments
+    ///
+    /// * `selection`: The selection to find a session for.
+    /// * `strategy`: Whether to try the name or the path lookup first.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some(name))` with the name of the existing session to switch to.
+    /// * `Ok(None)` if neither lookup found a session.
+    /// * `Err(AppError::Tmux)` if there was an issue communicating with tmux.
+    pub fn find_existing_session(
+        selection: &Selection,
+        strategy: SessionMatch,
+    ) -> Result<Option<String>> {
+        let find_by_name = || -> Result<Option<String>> {
+            if Self::session_exists(&selection.session_name)? {
+                Ok(Some(selection.session_name.clone()))
+            } else {
+                Ok(None)
+            }
+        };
+
+        let found = match strategy {
+            SessionMatch::Name => match find_by_name()? {
+                Some(name) => Some(name),
+                None => Self::find_session_by_path(&selection.path)?,
+            },
+            SessionMatch::Path => match Self::find_session_by_path(&selection.path)? {
+                Some(name) => Some(name),
+                None => find_by_name()?,
+            },
+        };
+        debug!(
+            "Existing session lookup for '{}' ({:?} first) found: {:?}",
+            selection.session_name, strategy, found
+        );
+        Ok(found)
+    }
+
+    /// Returns the name and working directory of the session the current client is attached to.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(Some((name, path)))` when running inside tmux.
+    /// * `Ok(None)` when not running inside a tmux session.
+    /// * `Err(AppError::Tmux)` if the `display-message` command failed.
+    pub fn current_session() -> Result<Option<(String, PathBuf)>> {
+        if !Self::is_inside_tmux_session() {
+            return Ok(None);
+        }
+        let display_message_cmd = DisplayMessage::new()
+            .print()
+            .message("#{session_name}\t#{session_path}");
+        let output = Tmux::with_command(display_message_cmd)
+            .output()
+            .map_err(|e| {
+                error!("Error while querying the current tmux session: {}", e);
+                AppError::Tmux(e)
+            })?;
+        let stdout = String::from_utf8_lossy(&output.stdout()).into_owned();
+        let current = Self::parse_session_paths(&stdout).into_iter().next();
+        debug!("Current tmux session: {:?}", current);
+        Ok(current)
+    }
+
     /// Checks if the application is currently running inside a tmux session
     /// by inspecting the `TMUX` environment variable.
     ///
@@ -180,43 +344,50 @@ impl SessionManager {
         inside
     }
 
-    /// Creates a new tmux session with the specified name and starting directory.
+    /// Creates a new tmux session with the specified name and starting directory, detached,
+    /// so neither the current terminal nor the current tmux client changes to it.
+    ///
+    /// Creating the session detached lets the caller set it up (e.g., run the `on_create` hook
+    /// in its first pane) before `switch_or_attach_to_session` switches or attaches to it.
     ///
-    /// If not already inside a tmux session (TMUX env var is not set),
-    /// this new session will be attached to the current terminal.
-    /// If inside an existing tmux session, this new session will be created detached.
-    /// In the latter case, `switch_or_attach_to_session` might be needed subsequently
-    /// if an immediate switch to the new session is desired.
+    /// Each entry of `environment` is passed as `-e NAME=value`, so the variables are part
+    /// of the session environment and visible to the first pane as well as later windows.
     ///
     /// # Arguments
     ///
     /// * `session_name`: The desired name for the new tmux session.
     /// * `start_directory`: The directory where the new session should start.
+    /// * `environment`: Environment variables to set in the new session.
     ///
     /// # Returns
     ///
-    /// * `Ok(())` if the session was created successfully (either attached or detached).
+    /// * `Ok(())` if the session was created successfully.
     /// * `Err(AppError::Session)` if the `tmux new-session` command failed.
-    pub fn create_new_session(session_name: &str, start_directory: &Path) -> Result<()> {
+    pub fn create_detached_session(
+        session_name: &str,
+        start_directory: &Path,
+        environment: &BTreeMap<String, String>,
+    ) -> Result<()> {
         debug!(
-            "Attempting to create new session '{}' at path '{}'. Inside tmux: {}",
+            "Attempting to create detached session '{}' at path '{}'.",
             session_name,
-            start_directory.display(),
-            Self::is_inside_tmux_session()
+            start_directory.display()
         );
-
         let mut new_session_cmd = NewSession::new();
         new_session_cmd = new_session_cmd.session_name(session_name);
         // Bind the Cow<'_, str> to a variable to extend its lifetime
         let start_dir_cow = start_directory.to_string_lossy();
         new_session_cmd = new_session_cmd.start_directory(start_dir_cow.as_ref());
+        new_session_cmd = new_session_cmd.detached();
 
-        if Self::is_inside_tmux_session() {
-            new_session_cmd = new_session_cmd.detached();
+        let mut tmux_cmd = new_session_cmd.build();
+        for (name, value) in environment {
+            debug!("Setting session environment variable '{}'.", name);
+            tmux_cmd.push_option("-e", format!("{name}={value}"));
         }
 
         Tmux::new()
-            .command(new_session_cmd)
+            .command(tmux_cmd)
             .output()
             .map(|_| {
                 debug!(
@@ -236,6 +407,282 @@ impl SessionManager {
             })
     }
 
+    /// Types `command` into the active pane of `session_name` and presses Enter.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_name`: The name of the target tmux session.
+    /// * `command`: The command line to type into the pane.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the keys were sent.
+    /// * `Err(AppError::Session)` if the `tmux send-keys` command failed.
+    pub fn send_command_to_session(session_name: &str, command: &str) -> Result<()> {
+        Self::send_command_to_pane(&format!("={session_name}:"), command)
+    }
+
+    /// Types `command` into the pane identified by the tmux target `target_pane` and presses Enter.
+    ///
+    /// The command is sent literally (`send-keys -l`), so words like `Enter` or `C-c` in it
+    /// are typed as text instead of being interpreted as key names.
+    fn send_command_to_pane(target_pane: &str, command: &str) -> Result<()> {
+        debug!("Sending command '{}' to pane '{}'.", command, target_pane);
+        let type_command = SendKeys::new()
+            .target_pane(target_pane)
+            .disable_lookup()
+            .key(command);
+        Self::run_layout_command(type_command, "send command to tmux pane", target_pane)?;
+        let press_enter = SendKeys::new().target_pane(target_pane).key("Enter");
+        Self::run_layout_command(press_enter, "send command to tmux pane", target_pane).map(|_| ())
+    }
+
+    /// Creates a detached session with the windows, panes and commands of `layout`.
+    ///
+    /// The first window is created by `new-session` in the root of the first layout window;
+    /// every further window is created with `new-window`. Panes after the first are created
+    /// with `split-window`, the pane commands are typed into each pane, and the window
+    /// layout (if any) is applied once all panes exist. Finally the first window is selected.
+    ///
+    /// The session is always created detached; use `switch_or_attach_to_session` afterwards.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_name`: The desired name for the new tmux session.
+    /// * `layout`: The imported tmuxinator or tmuxp layout.
+    /// * `environment`: Environment variables to set in the new session.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the session and all its windows and panes were created.
+    /// * `Err(AppError::Session)` if `layout` has no windows or any of the tmux commands failed.
+    pub fn create_session_from_layout(
+        session_name: &str,
+        layout: &SessionLayout,
+        environment: &BTreeMap<String, String>,
+    ) -> Result<()> {
+        debug!(
+            "Creating session '{}' from layout '{}' ({} windows).",
+            session_name,
+            layout.source.display(),
+            layout.windows.len()
+        );
+        if layout.windows.is_empty() {
+            return Err(AppError::Session(format!(
+                "Cannot create session '{session_name}': layout '{}' has no windows",
+                layout.source.display()
+            )));
+        }
+        let session_target = format!("={session_name}:");
+
+        let mut first_window_id = None;
+        for (window_index, window) in layout.windows.iter().enumerate() {
+            let window_root = layout.window_root(window);
+            let window_root_cow = window_root.to_string_lossy();
+
+            let window_id = if window_index == 0 {
+                Self::create_detached_session(session_name, &window_root, environment)?;
+                let window_id = Self::run_layout_command(
+                    DisplayMessage::new()
+                        .print()
+                        .target_pane(session_target.as_str())
+                        .message("#{window_id}"),
+                    "query the first window of tmux session",
+                    session_name,
+                )?;
+                if let Some(name) = &window.name {
+                    Self::run_layout_command(
+                        RenameWindow::new()
+                            .target_window(window_id.as_str())
+                            .new_name(name.as_str()),
+                        "rename window in tmux session",
+                        session_name,
+                    )?;
+                }
+                first_window_id = Some(window_id.clone());
+                window_id
+            } else {
+                let mut new_window_cmd = NewWindow::new()
+                    .detached()
+                    .print()
+                    .format("#{window_id}")
+                    .target_window(session_target.as_str())
+                    .start_directory(window_root_cow.as_ref());
+                if let Some(name) = &window.name {
+                    new_window_cmd = new_window_cmd.window_name(name.as_str());
+                }
+                Self::run_layout_command(
+                    new_window_cmd,
+                    "create window in tmux session",
+                    session_name,
+                )?
+            };
+
+            for (pane_index, pane) in window.panes.iter().enumerate() {
+                let pane_id = if pane_index == 0 {
+                    window_id.clone()
+                } else {
+                    let pane_root = layout.pane_root(window, pane);
+                    Self::run_layout_command(
+                        SplitWindow::new()
+                            .print()
+                            .format("#{pane_id}")
+                            .target_pane(window_id.as_str())
+                            .start_directory(pane_root.to_string_lossy().as_ref()),
+                        "split window in tmux session",
+                        session_name,
+                    )?
+                };
+                for command in &pane.commands {
+                    Self::send_command_to_pane(&pane_id, command)?;
+                }
+            }
+
+            if let Some(window_layout) = &window.layout {
+                Self::run_layout_command(
+                    SelectLayout::new()
+                        .target_pane(window_id.as_str())
+                        .layout_name(window_layout.as_str()),
+                    "apply window layout in tmux session",
+                    session_name,
+                )?;
+            }
+        }
+
+        if let Some(first_window_id) = first_window_id {
+            Self::run_layout_command(
+                SelectWindow::new().target_window(first_window_id.as_str()),
+                "select the first window of tmux session",
+                session_name,
+            )?;
+        }
+        debug!(
+            "Successfully created session '{}' from layout.",
+            session_name
+        );
+        Ok(())
+    }
+
+    /// Captures the windows and panes of a running session as a layout snapshot.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_name`: The name of the session to capture.
+    /// * `session_path`: The session's working directory, used as the layout root.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(SessionLayout)` with the session's windows, pane directories and running commands.
+    /// * `Err(AppError::Session)` if listing the windows or panes failed.
+    pub fn capture_session_layout(
+        session_name: &str,
+        session_path: &Path,
+    ) -> Result<SessionLayout> {
+        debug!("Capturing layout of session '{}'.", session_name);
+        let session_target = format!("={session_name}");
+        let windows_output = Self::run_layout_command(
+            ListWindows::new()
+                .format(WINDOW_FORMAT)
+                .target_session(session_target.as_str()),
+            "list windows of tmux session",
+            session_name,
+        )?;
+        let panes_output = Self::run_layout_command(
+            ListPanes::new()
+                .session()
+                .format(PANE_FORMAT)
+                .target(session_target.as_str()),
+            "list panes of tmux session",
+            session_name,
+        )?;
+        Ok(session_snapshot::layout_from_tmux_output(
+            session_name,
+            session_path,
+            &windows_output,
+            &panes_output,
+        ))
+    }
+
+    /// Runs a tmux command used while building a session and returns its trimmed stdout.
+    ///
+    /// `action` and `target` only describe the command in the error message.
+    fn run_layout_command<'a>(
+        command: impl Into<TmuxCommand<'a>>,
+        action: &str,
+        target: &str,
+    ) -> Result<String> {
+        Tmux::with_command(command)
+            .output()
+            .map(|output| String::from_utf8_lossy(&output.stdout()).trim().to_string())
+            .map_err(|e| {
+                let err_msg = format!("Failed to {action} '{target}': {e}");
+                error!("{}", err_msg);
+                AppError::Session(err_msg)
+            })
+    }
+
+    /// Runs the `on_create` hook for a session that was just created.
+    ///
+    /// The expanded hook command is typed into the first pane of the session. A failure is
+    /// reported as a warning and does not affect the session itself.
+    ///
+    /// # Arguments
+    ///
+    /// * `session_name`: The name of the newly created session.
+    /// * `hooks`: The hooks configured for the project.
+    /// * `context`: Values for the hook placeholders.
+    pub fn run_on_create_hook(session_name: &str, hooks: &HooksConfig, context: &HookContext) {
+        let Some(on_create) = &hooks.on_create else {
+            return;
+        };
+        let command = context.expand(on_create);
+        info!(session_name, command = %command, "Running on_create hook");
+        if let Err(e) = Self::send_command_to_session(session_name, &command) {
+            hooks::report_hook_failure("on_create", &e.to_string());
+        }
+    }
+
+    /// Kills the tmux session named exactly `session_name`.
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the session was killed.
+    /// * `Err(AppError::Session)` if the `tmux kill-session` command failed.
+    pub fn kill_session(session_name: &str) -> Result<()> {
+        debug!("Killing session '{}'.", session_name);
+        Tmux::with_command(KillSession::new().target_session(format!("={session_name}")))
+            .output()
+            .map(|_| debug!("Successfully killed session '{}'.", session_name))
+            .map_err(|e| {
+                let err_msg = format!("Failed to kill tmux session '{session_name}': {e}");
+                error!("{}", err_msg);
+                AppError::Session(err_msg)
+            })
+    }
+
+    /// Copies `text` into a new tmux paste buffer with `set-buffer -w`, which also sends it
+    /// to the system clipboard when tmux is set up for it (the `set-clipboard` option).
+    ///
+    /// # Returns
+    ///
+    /// * `Ok(())` if the paste buffer was set.
+    /// * `Err(AppError::Session)` if the `tmux set-buffer` command failed.
+    pub fn copy_to_paste_buffer(text: &str) -> Result<()> {
+        debug!("Copying '{}' to a tmux paste buffer.", text);
+        let mut set_buffer_cmd = TmuxCommand::new();
+        set_buffer_cmd.name("set-buffer");
+        set_buffer_cmd.push_flag("-w");
+        set_buffer_cmd.push_param(text);
+        Tmux::with_command(set_buffer_cmd)
+            .output()
+            .map(|_| debug!("Successfully set the tmux paste buffer."))
+            .map_err(|e| {
+                let err_msg = format!("Failed to copy '{text}' to a tmux paste buffer: {e}");
+                error!("{}", err_msg);
+                AppError::Session(err_msg)
+            })
+    }
+
     /// Switches the current tmux client to an existing session or attaches to it if outside tmux.
     ///
     /// If the program is run from within an existing tmux session (TMUX env var is set),
@@ -1,6 +1,6 @@
 use super::*;
 use crate::directory_scanner::{DirectoryEntry, DirectoryType};
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 #[test]
 fn test_generate_session_name_simple() {
@@ -162,7 +162,59 @@ fn test_create_new_project_directory_creates_parent() {
 }
 
 // Keep the existing note about tests requiring tmux interaction
-// Note: Tests for `is_tmux_server_running`, `session_exists`, `create_new_session`,
+// Note: Tests for `is_tmux_server_running`, `session_exists`, `create_detached_session`,
 // and `switch_or_attach_to_session` would require a live tmux server
 // or mocking the `tmux_interface` calls, which is complex for unit tests.
 // These functions are better suited for integration testing.
+
+#[test]
+fn test_parse_session_paths() {
+    let output = "main\t/home/user/dev/main\nrenamed\t/home/user/dev/project\n";
+    let sessions = SessionManager::parse_session_paths(output);
+    assert_eq!(
+        sessions,
+        vec![
+            ("main".to_string(), PathBuf::from("/home/user/dev/main")),
+            (
+                "renamed".to_string(),
+                PathBuf::from("/home/user/dev/project")
+            ),
+        ]
+    );
+}
+
+#[test]
+fn test_parse_session_paths_skips_malformed_lines() {
+    let output = "\nno-tab-here\n\t/missing/name\nempty-path\t\nok\t/some/path";
+    let sessions = SessionManager::parse_session_paths(output);
+    assert_eq!(
+        sessions,
+        vec![("ok".to_string(), PathBuf::from("/some/path"))]
+    );
+}
+
+#[test]
+fn test_match_session_by_path_finds_renamed_session() {
+    use tempfile::tempdir;
+
+    let temp_dir = tempdir().unwrap();
+    let project_path = std::fs::canonicalize(temp_dir.path()).unwrap();
+    let sessions = vec![
+        ("other".to_string(), PathBuf::from("/definitely/not/here")),
+        ("my-renamed-session".to_string(), project_path.clone()),
+    ];
+
+    assert_eq!(
+        SessionManager::match_session_by_path(&project_path, &sessions),
+        Some("my-renamed-session".to_string())
+    );
+}
+
+#[test]
+fn test_match_session_by_path_no_match() {
+    let sessions = vec![("other".to_string(), PathBuf::from("/definitely/not/here"))];
+    assert_eq!(
+        SessionManager::match_session_by_path(Path::new("/some/project"), &sessions),
+        None
+    );
+}
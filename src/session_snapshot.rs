@@ -0,0 +1,192 @@
+//! Saves and restores the layout of running project sessions.
+//!
+//! A snapshot records, for each sessionized project, the session's windows with their
+//! names and tmux layout strings, and each pane's working directory and running command.
+//! Snapshots are stored as a [`SessionLayout`] per session in a TOML file in the XDG data
+//! directory, so they survive tmux server restarts and reboots. Restoring recreates the
+//! windows and panes and restarts the recorded commands; shells are not restarted, since
+//! every new pane already runs one.
+
+use crate::error::{AppError, Result};
+use crate::session_layout::{PaneLayout, SessionLayout, WindowLayout};
+use serde_derive::{Deserialize, Serialize};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+use tracing::debug;
+
+/// Name of the snapshot file inside the application data directory.
+pub const SNAPSHOT_FILE_NAME: &str = "snapshots.toml";
+
+/// Format string for `tmux list-windows` used when capturing a session.
+pub const WINDOW_FORMAT: &str = "#{window_index}\t#{window_name}\t#{window_layout}";
+
+/// Format string for `tmux list-panes -s` used when capturing a session.
+pub const PANE_FORMAT: &str = "#{window_index}\t#{pane_current_path}\t#{pane_current_command}";
+
+/// Commands that are not restarted because a new pane starts a shell anyway.
+const SHELL_COMMANDS: &[&str] = &[
+    "bash", "zsh", "fish", "sh", "dash", "ksh", "mksh", "tcsh", "csh", "nu", "xonsh", "elvish",
+];
+
+/// All saved session snapshots.
+#[derive(Debug, Default, Serialize, Deserialize)]
+pub struct SnapshotStore {
+    /// Saved sessions, one per project. Session names are unique.
+    #[serde(default)]
+    pub sessions: Vec<SessionLayout>,
+}
+
+impl SnapshotStore {
+    /// Loads the snapshots from `file`. A missing file yields an empty store.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Io` if the file exists but cannot be read, and
+    /// `AppError::Layout` if it cannot be parsed.
+    pub fn load(file: &Path) -> Result<Self> {
+        let content = match fs::read_to_string(file) {
+            Ok(content) => content,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => {
+                debug!(file = %file.display(), "No snapshot file found, starting with no snapshots");
+                return Ok(Self::default());
+            }
+            Err(e) => return Err(e.into()),
+        };
+        let mut store: Self = toml::from_str(&content)
+            .map_err(|e| AppError::Layout(format!("Failed to parse '{}': {e}", file.display())))?;
+        for layout in &mut store.sessions {
+            layout.source = file.to_path_buf();
+            // Edited or truncated snapshots may lack windows or panes
+            layout.fill_empty();
+        }
+        debug!(file = %file.display(), count = store.sessions.len(), "Loaded session snapshots");
+        Ok(store)
+    }
+
+    /// Writes the snapshots to `file`, creating the parent directory if needed.
+    ///
+    /// # Errors
+    ///
+    /// Returns `AppError::Io` if the file cannot be written.
+    pub fn save(&self, file: &Path) -> Result<()> {
+        if let Some(parent) = file.parent() {
+            fs::create_dir_all(parent)?;
+        }
+        let content = toml::to_string_pretty(self)
+            .map_err(|e| AppError::Layout(format!("Failed to serialize session snapshots: {e}")))?;
+        fs::write(file, content)?;
+        debug!(file = %file.display(), count = self.sessions.len(), "Saved session snapshots");
+        Ok(())
+    }
+
+    /// Adds `layout`, replacing an older snapshot of the same session or project root.
+    pub fn insert(&mut self, layout: SessionLayout) {
+        self.sessions
+            .retain(|saved| saved.name != layout.name && saved.root != layout.root);
+        self.sessions.push(layout);
+        self.sessions.sort_by(|a, b| a.name.cmp(&b.name));
+    }
+
+    /// Returns the snapshot of the session named `name`.
+    pub fn find_by_name(&self, name: &str) -> Option<&SessionLayout> {
+        self.sessions.iter().find(|layout| layout.name == name)
+    }
+
+    /// Returns the snapshot whose project root is `path`.
+    pub fn find_by_path(&self, path: &Path) -> Option<&SessionLayout> {
+        self.sessions.iter().find(|layout| layout.root == path)
+    }
+}
+
+/// Builds a snapshot from the output of `tmux list-windows -F WINDOW_FORMAT` and
+/// `tmux list-panes -s -F PANE_FORMAT` for one session.
+///
+/// The first pane's directory becomes the window root; other panes only record their
+/// directory if it differs. A pane's running command is recorded unless it is a shell.
+///
+/// # Arguments
+///
+/// * `session_name` - The name of the captured session.
+/// * `session_path` - The session's working directory, used as the layout root.
+/// * `windows_output` - Output of `list-windows`, one window per line.
+/// * `panes_output` - Output of `list-panes -s`, one pane per line, in window order.
+pub fn layout_from_tmux_output(
+    session_name: &str,
+    session_path: &Path,
+    windows_output: &str,
+    panes_output: &str,
+) -> SessionLayout {
+    let panes: Vec<(&str, PathBuf, &str)> = panes_output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let window_index = fields.next()?;
+            let path = fields.next()?;
+            let command = fields.next().unwrap_or_default();
+            Some((window_index, PathBuf::from(path), command))
+        })
+        .collect();
+
+    let windows = windows_output
+        .lines()
+        .filter_map(|line| {
+            let mut fields = line.splitn(3, '\t');
+            let window_index = fields.next()?;
+            let name = fields.next()?;
+            let layout = fields.next().filter(|layout| !layout.is_empty());
+
+            let window_panes: Vec<&(&str, PathBuf, &str)> = panes
+                .iter()
+                .filter(|(pane_window, _, _)| *pane_window == window_index)
+                .collect();
+            let window_root = window_panes.first().map(|(_, path, _)| path.clone());
+
+            let pane_layouts = window_panes
+                .iter()
+                .map(|(_, path, command)| PaneLayout {
+                    root: (Some(path) != window_root.as_ref()).then(|| path.clone()),
+                    commands: if is_shell(command) {
+                        Vec::new()
+                    } else {
+                        vec![(*command).to_string()]
+                    },
+                })
+                .collect::<Vec<_>>();
+
+            Some(WindowLayout {
+                name: Some(name.to_string()),
+                root: window_root.filter(|root| root != session_path),
+                layout: layout.map(str::to_string),
+                panes: if pane_layouts.is_empty() {
+                    vec![PaneLayout::default()]
+                } else {
+                    pane_layouts
+                },
+            })
+        })
+        .collect::<Vec<_>>();
+
+    SessionLayout {
+        name: session_name.to_string(),
+        root: session_path.to_path_buf(),
+        windows: if windows.is_empty() {
+            vec![WindowLayout {
+                panes: vec![PaneLayout::default()],
+                ..Default::default()
+            }]
+        } else {
+            windows
+        },
+        source: PathBuf::new(),
+    }
+}
+
+/// Returns `true` if `command` is empty or a known shell.
+fn is_shell(command: &str) -> bool {
+    let command = command.trim().trim_start_matches('-');
+    command.is_empty() || SHELL_COMMANDS.contains(&command)
+}
+
+#[cfg(test)]
+mod tests;
@@ -0,0 +1,122 @@
+use super::*;
+use tempfile::tempdir;
+
+const WINDOWS: &str =
+    "1\teditor\tb25f,208x52,0,0{104x52,0,0,1,103x52,105,0,2}\n2\tserver\tc3a1,208x52,0,0,3\n";
+const PANES: &str = "1\t/srv/app\tnvim\n1\t/srv/app/web\t-zsh\n2\t/srv/app/api\tcargo\n";
+
+#[test]
+fn test_layout_from_tmux_output() {
+    let layout = layout_from_tmux_output("app", Path::new("/srv/app"), WINDOWS, PANES);
+
+    assert_eq!(layout.name, "app");
+    assert_eq!(layout.root, PathBuf::from("/srv/app"));
+    assert_eq!(layout.windows.len(), 2);
+
+    let editor = &layout.windows[0];
+    assert_eq!(editor.name.as_deref(), Some("editor"));
+    assert_eq!(
+        editor.layout.as_deref(),
+        Some("b25f,208x52,0,0{104x52,0,0,1,103x52,105,0,2}")
+    );
+    assert_eq!(editor.root, None);
+    assert_eq!(editor.panes[0].commands, vec!["nvim".to_string()]);
+    assert_eq!(editor.panes[0].root, None);
+    // Login shells are not restarted, but their directory is kept.
+    assert!(editor.panes[1].commands.is_empty());
+    assert_eq!(
+        layout.pane_root(editor, &editor.panes[1]),
+        PathBuf::from("/srv/app/web")
+    );
+
+    let server = &layout.windows[1];
+    assert_eq!(layout.window_root(server), PathBuf::from("/srv/app/api"));
+    assert_eq!(server.panes[0].commands, vec!["cargo".to_string()]);
+}
+
+#[test]
+fn test_layout_from_empty_output_has_one_pane() {
+    let layout = layout_from_tmux_output("empty", Path::new("/srv/empty"), "", "");
+    assert_eq!(layout.windows.len(), 1);
+    assert_eq!(layout.windows[0].panes.len(), 1);
+}
+
+#[test]
+fn test_store_insert_replaces_same_session_or_root() {
+    let mut store = SnapshotStore::default();
+    store.insert(layout_from_tmux_output("b", Path::new("/srv/b"), "", ""));
+    store.insert(layout_from_tmux_output("a", Path::new("/srv/a"), "", ""));
+    store.insert(layout_from_tmux_output(
+        "a-renamed",
+        Path::new("/srv/a"),
+        "",
+        "",
+    ));
+
+    let names: Vec<&str> = store.sessions.iter().map(|s| s.name.as_str()).collect();
+    assert_eq!(names, vec!["a-renamed", "b"]);
+    assert!(store.find_by_path(Path::new("/srv/a")).is_some());
+    assert!(store.find_by_name("a").is_none());
+}
+
+#[test]
+fn test_store_save_and_load_round_trip() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join("nested").join(SNAPSHOT_FILE_NAME);
+    let mut store = SnapshotStore::default();
+    store.insert(layout_from_tmux_output(
+        "app",
+        Path::new("/srv/app"),
+        WINDOWS,
+        PANES,
+    ));
+    store.save(&file).unwrap();
+
+    let loaded = SnapshotStore::load(&file).unwrap();
+    let saved = loaded.find_by_name("app").unwrap();
+    assert_eq!(saved.source, file);
+    assert_eq!(saved.windows, store.sessions[0].windows);
+}
+
+#[test]
+fn test_store_load_fills_empty_snapshot() {
+    let temp_dir = tempdir().unwrap();
+    let file = temp_dir.path().join(SNAPSHOT_FILE_NAME);
+    fs::write(
+        &file,
+        r#"
+[[sessions]]
+name = "empty"
+root = "/srv/empty"
+windows = []
+
+[[sessions]]
+name = "no-panes"
+root = "/srv/no-panes"
+
+[[sessions.windows]]
+name = "editor"
+panes = []
+"#,
+    )
+    .unwrap();
+
+    let store = SnapshotStore::load(&file).unwrap();
+
+    let empty = store.find_by_name("empty").unwrap();
+    assert_eq!(empty.windows.len(), 1);
+    assert_eq!(empty.windows[0].panes.len(), 1);
+    assert_eq!(
+        empty.window_root(&empty.windows[0]),
+        PathBuf::from("/srv/empty")
+    );
+    let no_panes = store.find_by_name("no-panes").unwrap();
+    assert_eq!(no_panes.windows[0].panes.len(), 1);
+}
+
+#[test]
+fn test_store_load_missing_file_is_empty() {
+    let temp_dir = tempdir().unwrap();
+    let store = SnapshotStore::load(&temp_dir.path().join(SNAPSHOT_FILE_NAME)).unwrap();
+    assert!(store.sessions.is_empty());
+}